@@ -103,3 +103,10 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
         }
     }
 }
+
+// 提前丢弃迭代器时释放余下的结点
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        for _ in self.by_ref() {}
+    }
+}
@@ -1,4 +1,5 @@
 pub mod iter;
+mod sort;
 
 #[cfg(test)]
 mod tests;
@@ -8,6 +9,7 @@ use std::mem;
 use std::ptr::NonNull;
 
 use self::iter::*;
+pub use self::sort::SortedIterator;
 
 #[derive(Default, Eq)]
 pub struct LinkedList<T> {
@@ -294,10 +296,11 @@ impl<T> IntoIterator for LinkedList<T> {
     type Item = T;
     type IntoIter = IntoIter<T>;
 
-    fn into_iter(self) -> Self::IntoIter {
+    fn into_iter(mut self) -> Self::IntoIter {
+        // 结点的所有权转交给迭代器，链表析构时不再释放它们
         IntoIter {
-            head: self.head,
-            tail: self.tail,
+            head: self.head.take(),
+            tail: self.tail.take(),
         }
     }
 }
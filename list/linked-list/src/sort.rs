@@ -0,0 +1,211 @@
+use std::cmp::Ordering;
+use std::ptr::NonNull;
+
+use crate::iter::IntoIter;
+use crate::{LinkedList, Node, NodePtr};
+
+impl<T> LinkedList<T> {
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        self.sort_by(T::cmp);
+    }
+
+    // 自底向上的归并排序：每趟把相邻的两段长为width的有序段归并，
+    // 只改动结点间的链接而不移动元素，额外空间O(1)，且是稳定的
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        if self.len < 2 {
+            return;
+        }
+
+        let mut width = 1;
+
+        loop {
+            let mut merges = 0;
+            let mut pass = Pass {
+                merged: Merged::default(),
+                p: self.head,
+                psize: 0,
+                q: None,
+                list: self,
+            };
+
+            while pass.p.is_some() {
+                merges += 1;
+
+                // 左段从p开始，右段从q开始
+                pass.q = pass.p;
+                pass.psize = 0;
+                while pass.psize < width
+                    && let Some(node) = pass.q
+                {
+                    pass.q = next(node);
+                    pass.psize += 1;
+                }
+                let mut qsize = width;
+
+                while pass.psize > 0 || (qsize > 0 && pass.q.is_some()) {
+                    let take_left = match (pass.p, pass.q) {
+                        _ if pass.psize == 0 => false,
+                        (_, None) => true,
+                        _ if qsize == 0 => true,
+                        // 相等时取左段，保证稳定
+                        (Some(a), Some(b)) => unsafe {
+                            compare(&a.as_ref().element, &b.as_ref().element) != Ordering::Greater
+                        },
+                        (None, Some(_)) => unreachable!(),
+                    };
+
+                    let node = if take_left {
+                        let node = pass.p.unwrap();
+                        pass.p = next(node);
+                        pass.psize -= 1;
+                        node
+                    } else {
+                        let node = pass.q.unwrap();
+                        pass.q = next(node);
+                        qsize -= 1;
+                        node
+                    };
+
+                    pass.merged.push(node);
+                }
+
+                pass.p = pass.q;
+            }
+
+            // 结点都已归并，drop只需收尾
+            drop(pass);
+
+            // 只归并了一次，整个链表已是一段
+            if merges == 1 {
+                return;
+            }
+
+            width *= 2;
+        }
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)));
+    }
+}
+
+fn next<T>(node: NonNull<Node<T>>) -> NodePtr<T> {
+    unsafe { node.as_ref().next }
+}
+
+// 一趟归并的进度：已归并的部分、左段余下的psize个结点、从q开始的其余结点
+// 尚未取出的结点的next都没有被改动过
+// drop时把它们重新串成完整的链表，compare恐慌时链表也保持完整
+struct Pass<'a, T> {
+    list: &'a mut LinkedList<T>,
+    merged: Merged<T>,
+    p: NodePtr<T>,
+    psize: usize,
+    q: NodePtr<T>,
+}
+
+impl<T> Drop for Pass<'_, T> {
+    fn drop(&mut self) {
+        let rest = match self.p {
+            Some(first) if self.psize > 0 => {
+                let mut last = first;
+                for _ in 1..self.psize {
+                    last = next(last).unwrap();
+                }
+                unsafe {
+                    last.as_mut().next = self.q;
+                }
+                self.p
+            }
+            _ => self.q,
+        };
+
+        match self.merged.tail {
+            Some(mut tail) => unsafe { tail.as_mut().next = rest },
+            None => self.merged.head = rest,
+        }
+
+        // 已归并部分的prev由push设好，只需重设其余结点的
+        let mut prev = self.merged.tail;
+        let mut curr = rest;
+        while let Some(mut node) = curr {
+            unsafe {
+                node.as_mut().prev = prev;
+            }
+            prev = curr;
+            curr = next(node);
+        }
+
+        self.list.head = self.merged.head;
+        self.list.tail = prev;
+    }
+}
+
+// 一趟归并中已经排好的前缀
+struct Merged<T> {
+    head: NodePtr<T>,
+    tail: NodePtr<T>,
+}
+
+impl<T> Default for Merged<T> {
+    fn default() -> Self {
+        Self {
+            head: None,
+            tail: None,
+        }
+    }
+}
+
+impl<T> Merged<T> {
+    fn push(&mut self, mut node: NonNull<Node<T>>) {
+        unsafe {
+            node.as_mut().prev = self.tail;
+        }
+
+        match self.tail {
+            Some(mut tail) => unsafe { tail.as_mut().next = Some(node) },
+            None => self.head = Some(node),
+        }
+
+        self.tail = Some(node);
+    }
+}
+
+// 对任意迭代器排序：先收集到链表中，再原地归并排序
+pub trait SortedIterator: Iterator + Sized {
+    fn sorted(self) -> IntoIter<Self::Item>
+    where
+        Self::Item: Ord,
+    {
+        self.sorted_by(Ord::cmp)
+    }
+
+    fn sorted_by<F>(self, compare: F) -> IntoIter<Self::Item>
+    where
+        F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+    {
+        let mut list: LinkedList<_> = self.collect();
+        list.sort_by(compare);
+        list.into_iter()
+    }
+
+    fn sorted_by_key<K, F>(self, mut f: F) -> IntoIter<Self::Item>
+    where
+        K: Ord,
+        F: FnMut(&Self::Item) -> K,
+    {
+        self.sorted_by(|a, b| f(a).cmp(&f(b)))
+    }
+}
+
+impl<I: Iterator> SortedIterator for I {}
@@ -1,4 +1,4 @@
-use crate::LinkedList;
+use crate::{LinkedList, SortedIterator};
 
 #[test]
 fn test_insert() {
@@ -38,3 +38,93 @@ fn test_single() {
     list.remove(0);
     assert!(list.is_empty());
 }
+
+#[test]
+fn test_into_iter() {
+    let list = LinkedList::from_iter([String::from("a"), String::from("b"), String::from("c")]);
+    let mut iter = list.into_iter();
+
+    assert_eq!(iter.next().as_deref(), Some("a"));
+    assert_eq!(iter.next_back().as_deref(), Some("c"));
+}
+
+#[test]
+fn test_sort() {
+    let mut list = LinkedList::from_iter((0..1000).map(|i| (i * 7919) % 1009));
+    let mut expected: Vec<_> = list.iter().copied().collect();
+    expected.sort();
+
+    list.sort();
+    assert_eq!(list.len(), 1000);
+    assert!(list.iter().eq(expected.iter()));
+    // 反向遍历依赖prev链接
+    assert!(list.iter().rev().eq(expected.iter().rev()));
+    assert_eq!(list.pop_back(), expected.pop());
+
+    let mut empty = LinkedList::<u32>::new();
+    empty.sort();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_sort_stable() {
+    let mut list = LinkedList::from_iter((0..500).map(|i| ((i * 31) % 7, i)));
+    list.sort_by_key(|&(k, _)| k);
+
+    let sorted: Vec<_> = list.iter().collect();
+    assert!(sorted.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_sort_by() {
+    let mut list = LinkedList::from_iter([3, 1, 4, 1, 5, 9, 2, 6]);
+    list.sort_by(|a, b| b.cmp(a));
+    assert_eq!(list, LinkedList::from_iter([9, 6, 5, 4, 3, 2, 1, 1]));
+}
+
+#[test]
+fn test_sort_panic() {
+    let expected: Vec<_> = {
+        let mut v: Vec<_> = (0..100).map(|i| ((i * 37) % 101).to_string()).collect();
+        v.sort();
+        v
+    };
+
+    // 在归并的不同阶段恐慌
+    for limit in [1, 57, 300, 520] {
+        let mut list = LinkedList::from_iter((0..100).map(|i| ((i * 37) % 101).to_string()));
+        let mut calls = 0;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                calls += 1;
+                assert!(calls < limit);
+                a.cmp(b)
+            });
+        }));
+        assert!(result.is_err());
+
+        // 链表仍完整，元素一个不少
+        let mut forward: Vec<_> = list.iter().cloned().collect();
+        let mut backward: Vec<_> = list.iter().rev().cloned().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(list.len(), 100);
+
+        forward.sort();
+        assert_eq!(forward, expected, "{limit}");
+    }
+}
+
+#[test]
+fn test_sorted() {
+    let sorted: Vec<_> = [5, 3, 8, 1].into_iter().sorted().collect();
+    assert_eq!(sorted, [1, 3, 5, 8]);
+
+    let words = ["pear", "fig", "banana", "kiwi"];
+    let by_len: Vec<_> = words.into_iter().sorted_by_key(|w| w.len()).collect();
+    assert_eq!(by_len, ["fig", "pear", "kiwi", "banana"]);
+
+    let top: Vec<_> = (0..100).rev().sorted_by(|a, b| a.cmp(b)).take(3).collect();
+    assert_eq!(top, [0, 1, 2]);
+}
@@ -1,29 +1,230 @@
-pub fn binary_search<T: Ord>(seq: &[T], x: T) -> Option<usize> {
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+// seq须按pred划分好：满足pred的元素全在前面
+// 返回首个不满足pred的下标，即满足pred的元素个数
+pub fn partition_point<T, P>(seq: &[T], mut pred: P) -> usize
+where
+    P: FnMut(&T) -> bool,
+{
     let mut low = 0;
     let mut high = seq.len();
 
     while low < high {
-        let mid = (low + high) / 2;
+        let mid = low + (high - low) / 2;
 
-        if x > seq[mid] {
+        if pred(&seq[mid]) {
             low = mid + 1;
         } else {
             high = mid;
         }
     }
 
-    (x == seq[low]).then_some(low)
+    low
+}
+
+// 首个不小于x的下标
+pub fn lower_bound<T, Q>(seq: &[T], x: &Q) -> usize
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    lower_bound_by(seq, |e| e.borrow().cmp(x))
+}
+
+// f给出元素相对于目标的大小
+pub fn lower_bound_by<T, F>(seq: &[T], mut f: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(seq, |e| f(e) == Ordering::Less)
+}
+
+pub fn lower_bound_by_key<T, K, Q, F>(seq: &[T], key: &Q, mut f: F) -> usize
+where
+    F: FnMut(&T) -> K,
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    lower_bound_by(seq, |e| f(e).borrow().cmp(key))
+}
+
+// 首个大于x的下标
+pub fn upper_bound<T, Q>(seq: &[T], x: &Q) -> usize
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    upper_bound_by(seq, |e| e.borrow().cmp(x))
+}
+
+pub fn upper_bound_by<T, F>(seq: &[T], mut f: F) -> usize
+where
+    F: FnMut(&T) -> Ordering,
+{
+    partition_point(seq, |e| f(e) != Ordering::Greater)
+}
+
+pub fn upper_bound_by_key<T, K, Q, F>(seq: &[T], key: &Q, mut f: F) -> usize
+where
+    F: FnMut(&T) -> K,
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    upper_bound_by(seq, |e| f(e).borrow().cmp(key))
+}
+
+// 与x相等的元素所在的区间，不存在时为插入点处的空区间
+pub fn equal_range<T, Q>(seq: &[T], x: &Q) -> Range<usize>
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    equal_range_by(seq, |e| e.borrow().cmp(x))
+}
+
+pub fn equal_range_by<T, F>(seq: &[T], mut f: F) -> Range<usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let start = lower_bound_by(seq, &mut f);
+    let end = start + upper_bound_by(&seq[start..], f);
+
+    start..end
+}
+
+pub fn equal_range_by_key<T, K, Q, F>(seq: &[T], key: &Q, mut f: F) -> Range<usize>
+where
+    F: FnMut(&T) -> K,
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    equal_range_by(seq, |e| f(e).borrow().cmp(key))
+}
+
+// 找到时返回首个相等元素的下标，否则返回保持有序的插入点
+pub fn binary_search<T, Q>(seq: &[T], x: &Q) -> Result<usize, usize>
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    binary_search_by(seq, |e| e.borrow().cmp(x))
+}
+
+pub fn binary_search_by<T, F>(seq: &[T], mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let i = lower_bound_by(seq, &mut f);
+
+    match seq.get(i) {
+        Some(e) if f(e) == Ordering::Equal => Ok(i),
+        _ => Err(i),
+    }
+}
+
+pub fn binary_search_by_key<T, K, Q, F>(seq: &[T], key: &Q, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> K,
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    binary_search_by(seq, |e| f(e).borrow().cmp(key))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::binary_search;
+    use super::*;
 
     #[test]
     fn basic() {
         let mut arr: [i32; 5] = [85, 63, 24, 56, 45];
         arr.sort();
 
-        assert_eq!(binary_search(&arr, 24), Some(0));
+        assert_eq!(binary_search(&arr, &24), Ok(0));
+    }
+
+    #[test]
+    fn empty() {
+        let arr: [i32; 0] = [];
+
+        assert_eq!(binary_search(&arr, &1), Err(0));
+        assert_eq!(lower_bound(&arr, &1), 0);
+        assert_eq!(upper_bound(&arr, &1), 0);
+        assert_eq!(equal_range(&arr, &1), 0..0);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let arr = [10, 20, 30];
+
+        assert_eq!(binary_search(&arr, &5), Err(0));
+        assert_eq!(binary_search(&arr, &35), Err(3));
+        assert_eq!(binary_search(&arr, &25), Err(2));
+        assert_eq!(upper_bound(&arr, &30), 3);
+        assert_eq!(equal_range(&arr, &99), 3..3);
+    }
+
+    #[test]
+    fn duplicates() {
+        let arr = [1, 2, 2, 2, 3, 5, 5];
+
+        assert_eq!(binary_search(&arr, &2), Ok(1));
+        assert_eq!(lower_bound(&arr, &2), 1);
+        assert_eq!(upper_bound(&arr, &2), 4);
+        assert_eq!(equal_range(&arr, &2), 1..4);
+        assert_eq!(equal_range(&arr, &5), 5..7);
+        assert_eq!(equal_range(&arr, &4), 5..5);
+    }
+
+    #[test]
+    fn borrowed() {
+        let words: Vec<String> = ["apple", "fig", "fig", "pear"].map(String::from).to_vec();
+
+        assert_eq!(binary_search(&words, "fig"), Ok(1));
+        assert_eq!(equal_range(&words, "fig"), 1..3);
+        assert_eq!(binary_search(&words, "kiwi"), Err(3));
+    }
+
+    #[test]
+    fn by_key() {
+        let records = [(1, "a"), (3, "b"), (3, "c"), (8, "d")];
+
+        assert_eq!(binary_search_by_key(&records, &3, |&(k, _)| k), Ok(1));
+        assert_eq!(equal_range_by_key(&records, &3, |&(k, _)| k), 1..3);
+        assert_eq!(lower_bound_by_key(&records, &4, |&(k, _)| k), 3);
+        assert_eq!(upper_bound_by_key(&records, &0, |&(k, _)| k), 0);
+
+        let names = [("ann", 1), ("bob", 2)];
+        assert_eq!(
+            binary_search_by_key(&names, "bob", |&(name, _)| name),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn by() {
+        // 降序序列，比较方向反过来
+        let arr = [9, 7, 7, 4, 1];
+
+        assert_eq!(equal_range_by(&arr, |e| 7.cmp(e)), 1..3);
+        assert_eq!(binary_search_by(&arr, |e| 5.cmp(e)), Err(3));
+        assert_eq!(partition_point(&arr, |&e| e > 4), 3);
+    }
+
+    // 与std的结果对照
+    #[test]
+    fn against_std() {
+        let arr: Vec<u32> = (0..200).map(|i| i / 3).collect();
+
+        for x in 0..70 {
+            assert_eq!(lower_bound(&arr, &x), arr.partition_point(|&e| e < x));
+            assert_eq!(upper_bound(&arr, &x), arr.partition_point(|&e| e <= x));
+            assert_eq!(
+                binary_search(&arr, &x).is_ok(),
+                arr.binary_search(&x).is_ok()
+            );
+        }
     }
 }
@@ -0,0 +1,158 @@
+use std::ops::Range;
+
+// 可二分的整数类型
+pub trait Integer: Copy + Ord {
+    // [self, end)的中点，向self取整；不会溢出
+    fn midpoint_down(self, end: Self) -> Self;
+
+    fn successor(self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty => $unsigned:ty),*) => {
+        $(
+            impl Integer for $ty {
+                fn midpoint_down(self, end: Self) -> Self {
+                    // 差值按无符号数计算，补码下对有符号数同样成立
+                    let half = (end.wrapping_sub(self) as $unsigned) / 2;
+                    self.wrapping_add(half as $ty)
+                }
+
+                fn successor(self) -> Self {
+                    self + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, u128 => u128, usize => usize,
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize
+);
+
+// pred在range上单调：先假后真；返回首个使pred为真的值，都为假时返回range.end
+pub fn search_first_true<T, P>(range: Range<T>, mut pred: P) -> T
+where
+    T: Integer,
+    P: FnMut(T) -> bool,
+{
+    let Range {
+        start: mut low,
+        end: mut high,
+    } = range;
+
+    while low < high {
+        let mid = low.midpoint_down(high);
+
+        if pred(mid) {
+            high = mid;
+        } else {
+            low = mid.successor();
+        }
+    }
+
+    low
+}
+
+// 浮点二分的终止条件：区间宽度不超过epsilon，或迭代次数用尽
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub epsilon: f64,
+    pub max_iterations: u32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            epsilon: 1e-9,
+            max_iterations: 100,
+        }
+    }
+}
+
+// pred在[range.start, range.end]上先假后真，返回分界点的近似值
+// 结果总是取在使pred为真的一侧
+pub fn bisect<P>(range: Range<f64>, tolerance: Tolerance, mut pred: P) -> f64
+where
+    P: FnMut(f64) -> bool,
+{
+    let Range {
+        start: mut low,
+        end: mut high,
+    } = range;
+
+    for _ in 0..tolerance.max_iterations {
+        let mid = low + (high - low) / 2.0;
+
+        // 精度已到极限，中点与端点重合
+        if high - low <= tolerance.epsilon || mid <= low || mid >= high {
+            break;
+        }
+
+        if pred(mid) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    high
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_true() {
+        assert_eq!(search_first_true(0..100u32, |x| x * x >= 50), 8);
+        assert_eq!(search_first_true(0..100u32, |_| true), 0);
+        assert_eq!(search_first_true(0..100u32, |_| false), 100);
+        assert_eq!(search_first_true(5..5u32, |_| true), 5);
+    }
+
+    #[test]
+    fn extremes() {
+        assert_eq!(search_first_true(i64::MIN..i64::MAX, |x| x >= -7), -7);
+        assert_eq!(search_first_true(i8::MIN..i8::MAX, |x| x > 100), 101);
+        assert_eq!(
+            search_first_true(0..u64::MAX, |x| x >= u64::MAX - 1),
+            u64::MAX - 1
+        );
+        assert_eq!(search_first_true(-3..-2i32, |_| false), -2);
+    }
+
+    // 每章单独占用整天，每天至少读多少页才能在6天内读完
+    #[test]
+    fn on_answer() {
+        let capacity = |c: usize| {
+            [7usize, 2, 5, 10, 8]
+                .iter()
+                .map(|p| p.div_ceil(c))
+                .sum::<usize>()
+        };
+        assert_eq!(search_first_true(1..11, |c| capacity(c) <= 6), 8);
+    }
+
+    #[test]
+    fn float() {
+        let sqrt2 = bisect(0.0..2.0, Tolerance::default(), |x| x * x >= 2.0);
+        assert!((sqrt2 - 2f64.sqrt()).abs() < 1e-9);
+        assert!(sqrt2 * sqrt2 >= 2.0);
+
+        // 迭代次数限制了精度
+        let rough = Tolerance {
+            epsilon: 0.0,
+            max_iterations: 3,
+        };
+        assert_eq!(bisect(0.0..8.0, rough, |x| x >= 3.3), 4.0);
+
+        // epsilon为0时一直二分到相邻的浮点数
+        let exact = Tolerance {
+            epsilon: 0.0,
+            max_iterations: u32::MAX,
+        };
+        assert_eq!(bisect(0.0..1.0, exact, |x| x >= 0.1), 0.1);
+    }
+}
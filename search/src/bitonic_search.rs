@@ -0,0 +1,82 @@
+use crate::{binary_search, binary_search_by, search_first_true};
+
+// seq先严格递增后严格递减，任一段都可以为空；返回最大元素的下标
+pub fn bitonic_peak<T: Ord>(seq: &[T]) -> Option<usize> {
+    let last = seq.len().checked_sub(1)?;
+
+    // 首个比后一个元素大的位置，即峰顶
+    Some(search_first_true(0..last, |i| seq[i] > seq[i + 1]))
+}
+
+// 先找峰顶，再分别二分递增段与递减段
+pub fn search_bitonic<T: Ord>(seq: &[T], x: &T) -> Option<usize> {
+    let peak = bitonic_peak(seq)?;
+    let (rising, falling) = seq.split_at(peak + 1);
+
+    binary_search(rising, x)
+        .or_else(|_| binary_search_by(falling, |e| x.cmp(e)).map(|i| peak + 1 + i))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bitonic(rise: usize, fall: usize) -> Vec<i32> {
+        let rising = (0..rise as i32).map(|i| i * 2);
+        let falling = (0..fall as i32).rev().map(|i| i * 2 + 1);
+        rising.chain(falling).collect()
+    }
+
+    #[test]
+    fn empty() {
+        let arr: [i32; 0] = [];
+
+        assert_eq!(bitonic_peak(&arr), None);
+        assert_eq!(search_bitonic(&arr, &0), None);
+    }
+
+    #[test]
+    fn peak() {
+        assert_eq!(bitonic_peak(&[1, 3, 8, 12, 4, 2]), Some(3));
+        assert_eq!(bitonic_peak(&[1, 2, 3]), Some(2));
+        assert_eq!(bitonic_peak(&[3, 2, 1]), Some(0));
+        assert_eq!(bitonic_peak(&[7]), Some(0));
+
+        for rise in 0..10 {
+            for fall in 0..10 {
+                let arr = bitonic(rise, fall);
+                let expected = arr
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|&(_, e)| e)
+                    .map(|(i, _)| i);
+
+                assert_eq!(bitonic_peak(&arr), expected, "{arr:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn against_linear() {
+        for rise in 0..10 {
+            for fall in 0..10 {
+                let arr = bitonic(rise, fall);
+
+                for x in -1..22 {
+                    let expected = arr.iter().position(|&e| e == x);
+                    assert_eq!(search_bitonic(&arr, &x), expected, "{arr:?} {x}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn strings() {
+        let arr = ["ant", "cat", "yak", "dog", "bee"];
+
+        assert_eq!(bitonic_peak(&arr), Some(2));
+        assert_eq!(search_bitonic(&arr, &"bee"), Some(4));
+        assert_eq!(search_bitonic(&arr, &"cow"), None);
+    }
+}
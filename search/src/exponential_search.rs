@@ -0,0 +1,123 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+use crate::binary_search::lower_bound_by;
+
+// 以1, 2, 4, ...的步长向后试探，圈定范围后再二分
+// 目标靠近开头时只需O(log i)次比较，i为目标所在的下标
+pub fn exponential_search<T, Q>(seq: &[T], x: &Q) -> Result<usize, usize>
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    exponential_search_by(seq, |e| e.borrow().cmp(x))
+}
+
+pub fn exponential_search_by<T, F>(seq: &[T], mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    let len = seq.len();
+    let mut bound = 1;
+
+    // 循环结束时，seq[..bound / 2]都小于目标
+    while bound <= len && f(&seq[bound - 1]) == Ordering::Less {
+        bound *= 2;
+    }
+
+    let low = bound / 2;
+    let i = low + lower_bound_by(&seq[low..bound.min(len)], &mut f);
+
+    match seq.get(i) {
+        Some(e) if f(e) == Ordering::Equal => Ok(i),
+        _ => Err(i),
+    }
+}
+
+pub fn exponential_search_by_key<T, K, Q, F>(seq: &[T], key: &Q, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> K,
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    exponential_search_by(seq, |e| f(e).borrow().cmp(key))
+}
+
+// 长度未知的有序序列：f(i)给出第i个元素相对于目标的大小
+// 序列须最终出现不小于目标的元素，否则不会终止
+pub fn exponential_search_unbounded<F>(mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(usize) -> Ordering,
+{
+    let mut bound = 1;
+
+    while f(bound - 1) == Ordering::Less {
+        bound *= 2;
+    }
+
+    let (mut low, mut high) = (bound / 2, bound - 1);
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if f(mid) == Ordering::Less {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    match f(low) {
+        Ordering::Equal => Ok(low),
+        _ => Err(low),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_search;
+
+    #[test]
+    fn empty() {
+        let arr: [u32; 0] = [];
+        assert_eq!(exponential_search(&arr, &3), Err(0));
+    }
+
+    #[test]
+    fn against_binary_search() {
+        for len in 0..40 {
+            let arr: Vec<u32> = (0..len).map(|i| i / 2 * 3).collect();
+
+            for x in 0..70 {
+                assert_eq!(
+                    exponential_search(&arr, &x),
+                    binary_search(&arr, &x),
+                    "{arr:?} {x}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn by_key() {
+        let records = [(2, 'a'), (4, 'b'), (4, 'c'), (9, 'd')];
+
+        assert_eq!(exponential_search_by_key(&records, &4, |&(k, _)| k), Ok(1));
+        assert_eq!(exponential_search_by_key(&records, &5, |&(k, _)| k), Err(3));
+    }
+
+    #[test]
+    fn unbounded() {
+        // 平方数序列
+        assert_eq!(
+            exponential_search_unbounded(|i| (i * i).cmp(&1_000_000)),
+            Ok(1000)
+        );
+        assert_eq!(
+            exponential_search_unbounded(|i| (i * i).cmp(&1_000_001)),
+            Err(1001)
+        );
+        assert_eq!(exponential_search_unbounded(|i| i.cmp(&0)), Ok(0));
+    }
+}
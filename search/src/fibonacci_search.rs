@@ -0,0 +1,98 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+
+// 按斐波那契数切分区间，只用加减法计算探测位置
+pub fn fibonacci_search<T, Q>(seq: &[T], x: &Q) -> Result<usize, usize>
+where
+    T: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    fibonacci_search_by(seq, |e| e.borrow().cmp(x))
+}
+
+pub fn fibonacci_search_by<T, F>(seq: &[T], mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> Ordering,
+{
+    // 在[low, low + n)内找首个不小于目标的位置
+    let mut low = 0;
+    let mut n = seq.len();
+
+    // 相邻的两个斐波那契数，a为不超过n的最大者
+    let (mut a, mut b) = (1, 1);
+    while b <= n {
+        (a, b) = (b, a + b);
+    }
+
+    while n > 0 {
+        while a > n {
+            (a, b) = (b - a, a);
+        }
+
+        let mid = low + a - 1;
+
+        if f(&seq[mid]) == Ordering::Less {
+            low = mid + 1;
+            n -= a;
+        } else {
+            n = a - 1;
+        }
+    }
+
+    match seq.get(low) {
+        Some(e) if f(e) == Ordering::Equal => Ok(low),
+        _ => Err(low),
+    }
+}
+
+pub fn fibonacci_search_by_key<T, K, Q, F>(seq: &[T], key: &Q, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(&T) -> K,
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    fibonacci_search_by(seq, |e| f(e).borrow().cmp(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_search;
+
+    #[test]
+    fn empty() {
+        let arr: [u32; 0] = [];
+        assert_eq!(fibonacci_search(&arr, &3), Err(0));
+    }
+
+    #[test]
+    fn against_binary_search() {
+        for len in 0..60 {
+            let arr: Vec<u32> = (0..len).map(|i| i / 3 * 2).collect();
+
+            for x in 0..45 {
+                assert_eq!(
+                    fibonacci_search(&arr, &x),
+                    binary_search(&arr, &x),
+                    "{arr:?} {x}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn borrowed() {
+        let words: Vec<String> = ["ant", "bee", "cat", "dog"].map(String::from).to_vec();
+
+        assert_eq!(fibonacci_search(&words, "cat"), Ok(2));
+        assert_eq!(fibonacci_search(&words, "cow"), Err(3));
+    }
+
+    #[test]
+    fn by_key() {
+        let records = [(1, 'a'), (1, 'b'), (5, 'c')];
+
+        assert_eq!(fibonacci_search_by_key(&records, &1, |&(k, _)| k), Ok(0));
+        assert_eq!(fibonacci_search_by_key(&records, &6, |&(k, _)| k), Err(3));
+    }
+}
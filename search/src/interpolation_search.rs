@@ -0,0 +1,156 @@
+// 可插值的键：映射到整数轴上，保持原有的次序
+pub trait Interpolate: Ord {
+    fn position(&self) -> i128;
+}
+
+macro_rules! impl_interpolate {
+    ($($ty:ty),*) => {
+        $(
+            impl Interpolate for $ty {
+                fn position(&self) -> i128 {
+                    *self as i128
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl Interpolate for char {
+    fn position(&self) -> i128 {
+        *self as i128
+    }
+}
+
+impl<T: Interpolate + ?Sized> Interpolate for &T {
+    fn position(&self) -> i128 {
+        (**self).position()
+    }
+}
+
+// 按目标在首尾元素之间的比例估计位置，
+// 键均匀分布时期望O(log log n)次比较，最坏O(n)
+pub fn interpolation_search<T: Interpolate>(seq: &[T], x: &T) -> Result<usize, usize> {
+    interpolation_search_by_key(seq, &x, |e| e)
+}
+
+pub fn interpolation_search_by_key<'a, T, K, F>(
+    seq: &'a [T],
+    key: &K,
+    mut f: F,
+) -> Result<usize, usize>
+where
+    K: Interpolate,
+    F: FnMut(&'a T) -> K,
+{
+    let target = key.position();
+
+    // 不变式：seq[..low]都小于目标，seq[high..]都不小于目标
+    let mut low = 0;
+    let mut high = seq.len();
+
+    while low < high {
+        let first = f(&seq[low]).position();
+        if target <= first {
+            break;
+        }
+
+        let last = f(&seq[high - 1]).position();
+        if target > last {
+            low = high;
+            break;
+        }
+
+        // first < target <= last，估计值落在[low, high - 1]内
+        // 两个因子都小于2⁶⁴，乘积在u128内不会溢出
+        let span = (high - 1 - low) as u128;
+        let offset = (target - first) as u128 * span / (last - first) as u128;
+        let mid = low + offset as usize;
+
+        if f(&seq[mid]).position() < target {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    match seq.get(low) {
+        Some(e) if f(e).position() == target => Ok(low),
+        _ => Err(low),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::binary_search;
+
+    #[test]
+    fn empty() {
+        let arr: [u64; 0] = [];
+        assert_eq!(interpolation_search(&arr, &3), Err(0));
+    }
+
+    #[test]
+    fn uniform() {
+        let ids: Vec<u64> = (0..10_000).map(|i| i * 37 + 5).collect();
+
+        assert_eq!(interpolation_search(&ids, &5), Ok(0));
+        assert_eq!(interpolation_search(&ids, &(37 * 4321 + 5)), Ok(4321));
+        assert_eq!(interpolation_search(&ids, &6), Err(1));
+        assert_eq!(interpolation_search(&ids, &u64::MAX), Err(10_000));
+    }
+
+    #[test]
+    fn against_binary_search() {
+        // 含重复与极端值，分布很不均匀
+        let arr: Vec<i64> = vec![
+            i64::MIN,
+            -50,
+            -50,
+            -3,
+            0,
+            0,
+            0,
+            7,
+            1 << 40,
+            i64::MAX,
+            i64::MAX,
+        ];
+
+        for x in [
+            i64::MIN,
+            -51,
+            -50,
+            -4,
+            0,
+            1,
+            7,
+            8,
+            1 << 40,
+            i64::MAX - 1,
+            i64::MAX,
+        ] {
+            assert_eq!(
+                interpolation_search(&arr, &x),
+                binary_search(&arr, &x),
+                "{x}"
+            );
+        }
+    }
+
+    #[test]
+    fn by_key() {
+        let records = [(3u32, "c"), (10, "j"), (10, "k"), (26, "z")];
+
+        assert_eq!(
+            interpolation_search_by_key(&records, &10, |&(k, _)| k),
+            Ok(1)
+        );
+        assert_eq!(
+            interpolation_search_by_key(&records, &11, |&(k, _)| k),
+            Err(3)
+        );
+    }
+}
@@ -1,3 +1,29 @@
 mod binary_search;
+mod bisection;
+mod bitonic_search;
+mod exponential_search;
+mod fibonacci_search;
+mod interpolation_search;
+pub mod pattern;
+mod rotated_search;
+mod staircase_search;
+mod ternary_search;
 
-pub use self::binary_search::binary_search;
+pub use self::binary_search::{
+    binary_search, binary_search_by, binary_search_by_key, equal_range, equal_range_by,
+    equal_range_by_key, lower_bound, lower_bound_by, lower_bound_by_key, partition_point,
+    upper_bound, upper_bound_by, upper_bound_by_key,
+};
+pub use self::bisection::{Integer, Tolerance, bisect, search_first_true};
+pub use self::bitonic_search::{bitonic_peak, search_bitonic};
+pub use self::exponential_search::{
+    exponential_search, exponential_search_by, exponential_search_by_key,
+    exponential_search_unbounded,
+};
+pub use self::fibonacci_search::{fibonacci_search, fibonacci_search_by, fibonacci_search_by_key};
+pub use self::interpolation_search::{
+    Interpolate, interpolation_search, interpolation_search_by_key,
+};
+pub use self::rotated_search::{find_rotation_point, search_rotated};
+pub use self::staircase_search::staircase_search;
+pub use self::ternary_search::{ternary_search, ternary_search_f64};
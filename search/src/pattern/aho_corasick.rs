@@ -0,0 +1,650 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::io::{self, ErrorKind, Read};
+
+use crate::binary_search_by_key;
+
+const ROOT: usize = 0;
+// 流式读入时每次追加的字节数
+const CHUNK: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchKind {
+    // 报告所有匹配，包括相互重叠的，按结束位置升序
+    #[default]
+    Overlapping,
+    // 互不重叠：取起点最靠左的，同一起点取在模式列表中靠前的
+    LeftmostFirst,
+    // 互不重叠：取起点最靠左的，同一起点取最长的
+    LeftmostLongest,
+}
+
+// 模式pattern匹配了haystack[start..end]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+// 多模式匹配：在模式串的trie上加失配指针，一趟扫描找出所有模式
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+    states: Vec<State>,
+    // 各模式的长度
+    lens: Vec<usize>,
+    kind: MatchKind,
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    // 按字节升序排列的trie边
+    edges: Vec<(u8, usize)>,
+    fail: usize,
+    // 沿失配链最近的、有模式在此结束的状态
+    dict: Option<usize>,
+    // 恰好在此结束的模式，编号升序
+    patterns: Vec<usize>,
+    depth: usize,
+}
+
+impl State {
+    fn new(depth: usize) -> Self {
+        Self {
+            edges: Vec::new(),
+            fail: ROOT,
+            dict: None,
+            patterns: Vec::new(),
+            depth,
+        }
+    }
+
+    fn goto(&self, byte: u8) -> Option<usize> {
+        binary_search_by_key(&self.edges, &byte, |&(b, _)| b)
+            .ok()
+            .map(|i| self.edges[i].1)
+    }
+}
+
+impl AhoCorasick {
+    pub fn new<I, P>(patterns: I, kind: MatchKind) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let mut states = vec![State::new(0)];
+        let mut lens = Vec::new();
+
+        for (id, pattern) in patterns.into_iter().enumerate() {
+            let pattern = pattern.as_ref();
+            let mut s = ROOT;
+
+            for &byte in pattern {
+                s = match binary_search_by_key(&states[s].edges, &byte, |&(b, _)| b) {
+                    Ok(i) => states[s].edges[i].1,
+                    Err(i) => {
+                        let child = states.len();
+                        states.push(State::new(states[s].depth + 1));
+                        states[s].edges.insert(i, (byte, child));
+                        child
+                    }
+                };
+            }
+
+            states[s].patterns.push(id);
+            lens.push(pattern.len());
+        }
+
+        let mut ac = Self { states, lens, kind };
+
+        // 按层次遍历，较浅状态的失配指针总是先算出
+        let mut queue = VecDeque::from([ROOT]);
+        while let Some(s) = queue.pop_front() {
+            for i in 0..ac.states[s].edges.len() {
+                let (byte, child) = ac.states[s].edges[i];
+                let fail = if s == ROOT {
+                    ROOT
+                } else {
+                    ac.next(ac.states[s].fail, byte)
+                };
+
+                ac.states[child].fail = fail;
+                ac.states[child].dict = if ac.states[fail].patterns.is_empty() {
+                    ac.states[fail].dict
+                } else {
+                    Some(fail)
+                };
+                queue.push_back(child);
+            }
+        }
+
+        ac
+    }
+
+    pub fn patterns_len(&self) -> usize {
+        self.lens.len()
+    }
+
+    pub fn match_kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    pub fn find_iter<'a>(&'a self, haystack: &'a [u8]) -> FindIter<'a> {
+        FindIter {
+            ac: self,
+            haystack,
+            scan: Scan::new(),
+        }
+    }
+
+    // 边读边匹配，只缓存尚未确定的部分，内存与输入长度无关
+    pub fn stream_find_iter<R: Read>(&self, reader: R) -> StreamFindIter<'_, R> {
+        StreamFindIter {
+            ac: self,
+            stream: Stream {
+                reader,
+                buf: Vec::new(),
+                filled: 0,
+                offset: 0,
+                keep: 0,
+                eof: false,
+            },
+            scan: Scan::new(),
+        }
+    }
+
+    fn next(&self, mut s: usize, byte: u8) -> usize {
+        loop {
+            if let Some(t) = self.states[s].goto(byte) {
+                return t;
+            }
+            if s == ROOT {
+                return ROOT;
+            }
+            s = self.states[s].fail;
+        }
+    }
+
+    // 以s结尾的最长模式，即失配链上首个有模式结束的状态
+    fn longest_output(&self, s: usize) -> Option<usize> {
+        if self.states[s].patterns.is_empty() {
+            self.states[s].dict
+        } else {
+            Some(s)
+        }
+    }
+
+    fn find_next<H: Haystack>(
+        &self,
+        scan: &mut Scan,
+        hay: &mut H,
+    ) -> Result<Option<Match>, H::Error> {
+        if scan.done {
+            return Ok(None);
+        }
+
+        match self.kind {
+            MatchKind::Overlapping => self.overlapping(scan, hay),
+            MatchKind::LeftmostFirst | MatchKind::LeftmostLongest => self.leftmost(scan, hay),
+        }
+    }
+
+    fn overlapping<H: Haystack>(
+        &self,
+        scan: &mut Scan,
+        hay: &mut H,
+    ) -> Result<Option<Match>, H::Error> {
+        loop {
+            // 沿字典链依次报告在当前位置结束的模式，由长到短
+            while let Some(o) = scan.output {
+                if let Some(&pattern) = self.states[o].patterns.get(scan.emitted) {
+                    scan.emitted += 1;
+                    return Ok(Some(Match {
+                        pattern,
+                        start: scan.pos - self.lens[pattern],
+                        end: scan.pos,
+                    }));
+                }
+
+                scan.output = self.states[o].dict;
+                scan.emitted = 0;
+            }
+
+            let Some(byte) = hay.byte(scan.pos)? else {
+                scan.done = true;
+                return Ok(None);
+            };
+
+            scan.state = self.next(scan.state, byte);
+            scan.pos += 1;
+            scan.output = Some(scan.state);
+            hay.release(scan.pos);
+        }
+    }
+
+    fn leftmost<H: Haystack>(
+        &self,
+        scan: &mut Scan,
+        hay: &mut H,
+    ) -> Result<Option<Match>, H::Error> {
+        let mut state = ROOT;
+        let mut pos = scan.pos;
+        let mut best: Option<Match> = None;
+
+        let eof = loop {
+            // 同一结束位置上只有最长的模式起点最靠左，其余不会更优
+            if let Some(o) = self.longest_output(state) {
+                let pattern = self.states[o].patterns[0];
+                let found = Match {
+                    pattern,
+                    start: pos - self.lens[pattern],
+                    end: pos,
+                };
+
+                let better = match best {
+                    None => true,
+                    Some(b) if found.start != b.start => found.start < b.start,
+                    // 同一起点，后发现的匹配总是更长
+                    Some(b) => self.kind == MatchKind::LeftmostLongest || pattern < b.pattern,
+                };
+
+                if better {
+                    best = Some(found);
+                }
+            }
+
+            // 之后的匹配至少从pos - depth开始，不可能更靠左了
+            if let Some(b) = best
+                && pos - self.states[state].depth > b.start
+            {
+                break false;
+            }
+
+            // 之后要么从pos - depth起继续匹配，要么从已有匹配处重新开始
+            hay.release(best.map_or(pos - self.states[state].depth, |b| b.start));
+
+            match hay.byte(pos)? {
+                Some(byte) => {
+                    state = self.next(state, byte);
+                    pos += 1;
+                }
+                None => break true,
+            }
+        };
+
+        let Some(m) = best else {
+            scan.done = true;
+            return Ok(None);
+        };
+
+        if m.start < m.end {
+            scan.pos = m.end;
+        } else if eof && pos == m.end {
+            scan.done = true;
+        } else {
+            // 空匹配之后跳过一个字节，避免原地重复
+            scan.pos = m.end + 1;
+        }
+
+        Ok(Some(m))
+    }
+}
+
+// 扫描的进度，可跨多次调用继续
+struct Scan {
+    pos: usize,
+    state: usize,
+    // 重叠模式下正在报告的字典链状态，及其中已报告的个数
+    output: Option<usize>,
+    emitted: usize,
+    done: bool,
+}
+
+impl Scan {
+    fn new() -> Self {
+        Self {
+            pos: 0,
+            state: ROOT,
+            output: Some(ROOT),
+            emitted: 0,
+            done: false,
+        }
+    }
+}
+
+// 按绝对位置逐字节读取的输入
+trait Haystack {
+    type Error;
+
+    fn byte(&mut self, pos: usize) -> Result<Option<u8>, Self::Error>;
+
+    // pos之前的字节不会再被读取
+    fn release(&mut self, pos: usize) {
+        let _ = pos;
+    }
+}
+
+impl Haystack for &[u8] {
+    type Error = Infallible;
+
+    fn byte(&mut self, pos: usize) -> Result<Option<u8>, Infallible> {
+        Ok(self.get(pos).copied())
+    }
+}
+
+struct Stream<R> {
+    reader: R,
+    // buf[..filled]缓存输入的[offset, offset + filled)，其后为可复用的空闲空间
+    buf: Vec<u8>,
+    filled: usize,
+    offset: usize,
+    // keep之前的字节不会再被读取，缓冲区满时可以丢弃
+    keep: usize,
+    eof: bool,
+}
+
+impl<R: Read> Haystack for Stream<R> {
+    type Error = io::Error;
+
+    fn byte(&mut self, pos: usize) -> io::Result<Option<u8>> {
+        while pos >= self.offset + self.filled {
+            if self.eof {
+                return Ok(None);
+            }
+
+            // 先把仍需保留的部分移到开头，腾出空间仍不够时才扩容
+            if self.filled == self.buf.len() {
+                let consumed = self.keep - self.offset;
+                self.buf.copy_within(consumed..self.filled, 0);
+                self.filled -= consumed;
+                self.offset = self.keep;
+
+                if self.filled == self.buf.len() {
+                    self.buf.resize(self.buf.len() + CHUNK, 0);
+                }
+            }
+
+            let read = loop {
+                match self.reader.read(&mut self.buf[self.filled..]) {
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    read => break read,
+                }
+            };
+
+            let n = read?;
+            self.filled += n;
+            self.eof = n == 0;
+        }
+
+        Ok(Some(self.buf[pos - self.offset]))
+    }
+
+    fn release(&mut self, pos: usize) {
+        self.keep = self.keep.max(pos);
+    }
+}
+
+pub struct FindIter<'a> {
+    ac: &'a AhoCorasick,
+    haystack: &'a [u8],
+    scan: Scan,
+}
+
+impl Iterator for FindIter<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        let Ok(m) = self.ac.find_next(&mut self.scan, &mut self.haystack);
+        m
+    }
+}
+
+pub struct StreamFindIter<'a, R> {
+    ac: &'a AhoCorasick,
+    stream: Stream<R>,
+    scan: Scan,
+}
+
+impl<R: Read> Iterator for StreamFindIter<'_, R> {
+    type Item = io::Result<Match>;
+
+    fn next(&mut self) -> Option<io::Result<Match>> {
+        match self.ac.find_next(&mut self.scan, &mut self.stream) {
+            Ok(m) => m.map(Ok),
+            Err(e) => {
+                // 出错后不再继续
+                self.scan.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read};
+
+    use super::*;
+
+    fn triples(matches: impl Iterator<Item = Match>) -> Vec<(usize, usize, usize)> {
+        matches.map(|m| (m.pattern, m.start, m.end)).collect()
+    }
+
+    fn find(patterns: &[&str], kind: MatchKind, haystack: &str) -> Vec<(usize, usize, usize)> {
+        triples(AhoCorasick::new(patterns, kind).find_iter(haystack.as_bytes()))
+    }
+
+    // 逐个位置、逐个模式地比较
+    fn naive(patterns: &[&[u8]], kind: MatchKind, haystack: &[u8]) -> Vec<(usize, usize, usize)> {
+        let occurs = |start: usize| {
+            patterns
+                .iter()
+                .enumerate()
+                .filter(move |(_, p)| haystack[start..].starts_with(p))
+                .map(move |(id, p)| (id, start, start + p.len()))
+        };
+
+        if kind == MatchKind::Overlapping {
+            let mut all: Vec<_> = (0..=haystack.len()).flat_map(occurs).collect();
+            // 按结束位置升序，同一结束位置由长到短
+            all.sort_by_key(|&(id, start, end)| (end, start, id));
+            return all;
+        }
+
+        let mut found = Vec::new();
+        let mut pos = 0;
+
+        while let Some(start) = (pos..=haystack.len()).find(|&s| occurs(s).next().is_some()) {
+            let m = if kind == MatchKind::LeftmostFirst {
+                occurs(start).next().unwrap()
+            } else {
+                occurs(start)
+                    .max_by_key(|&(id, _, end)| (end, std::cmp::Reverse(id)))
+                    .unwrap()
+            };
+
+            found.push(m);
+            pos = if m.1 < m.2 { m.2 } else { m.2 + 1 };
+        }
+
+        found
+    }
+
+    // 每次只给出一个字节的读取器
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((&b, rest)) if !buf.is_empty() => {
+                    buf[0] = b;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn overlapping() {
+        let patterns = ["he", "she", "his", "hers"];
+
+        assert_eq!(
+            find(&patterns, MatchKind::Overlapping, "ushers"),
+            [(1, 1, 4), (0, 2, 4), (3, 2, 6)]
+        );
+    }
+
+    #[test]
+    fn leftmost() {
+        assert_eq!(
+            find(&["Samwise", "Sam"], MatchKind::LeftmostFirst, "Samwise"),
+            [(0, 0, 7)]
+        );
+        assert_eq!(
+            find(&["Sam", "Samwise"], MatchKind::LeftmostFirst, "Samwise"),
+            [(0, 0, 3)]
+        );
+        assert_eq!(
+            find(&["Sam", "Samwise"], MatchKind::LeftmostLongest, "Samwise"),
+            [(1, 0, 7)]
+        );
+
+        // 较短的模式在前面结束，但起点更靠右
+        assert_eq!(
+            find(&["bcd", "abcde"], MatchKind::LeftmostFirst, "xabcdex"),
+            [(1, 1, 6)]
+        );
+    }
+
+    #[test]
+    fn empty_pattern() {
+        assert_eq!(
+            find(&["", "a"], MatchKind::LeftmostFirst, "aa"),
+            [(0, 0, 0), (0, 1, 1), (0, 2, 2)]
+        );
+        assert_eq!(
+            find(&["", "a"], MatchKind::LeftmostLongest, "aba"),
+            [(1, 0, 1), (0, 1, 1), (1, 2, 3), (0, 3, 3)]
+        );
+        assert!(find(&[], MatchKind::LeftmostLongest, "abc").is_empty());
+    }
+
+    #[test]
+    fn against_naive() {
+        // {a, b}上长度不超过3的所有串
+        let words: Vec<Vec<u8>> = (0..15usize)
+            .map(|code| {
+                let len = (code + 1).ilog2() as usize;
+                let bits = code + 1 - (1 << len);
+                (0..len).map(|i| b"ab"[bits >> i & 1]).collect()
+            })
+            .collect();
+
+        let mut sets: Vec<Vec<&[u8]>> = Vec::new();
+        for a in &words {
+            sets.push(vec![a]);
+            for b in &words {
+                sets.push(vec![a, b]);
+                for c in words.iter().step_by(4) {
+                    sets.push(vec![a, b, c]);
+                }
+            }
+        }
+
+        let haystacks: Vec<Vec<u8>> = (0..6)
+            .flat_map(|len| {
+                (0..1usize << len).map(move |bits| (0..len).map(|i| b"ab"[bits >> i & 1]).collect())
+            })
+            .collect();
+
+        for patterns in &sets {
+            for kind in [
+                MatchKind::Overlapping,
+                MatchKind::LeftmostFirst,
+                MatchKind::LeftmostLongest,
+            ] {
+                let ac = AhoCorasick::new(patterns, kind);
+
+                for haystack in &haystacks {
+                    assert_eq!(
+                        triples(ac.find_iter(haystack)),
+                        naive(patterns, kind, haystack),
+                        "{kind:?} {patterns:?} {haystack:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stream() {
+        let patterns = ["error", "err", "warn", "fatal error"];
+        let log = "warn: disk; err: fatal error at 12\n".repeat(2000);
+
+        for kind in [
+            MatchKind::Overlapping,
+            MatchKind::LeftmostFirst,
+            MatchKind::LeftmostLongest,
+        ] {
+            let ac = AhoCorasick::new(patterns, kind);
+            let expected: Vec<_> = ac.find_iter(log.as_bytes()).collect();
+
+            let streamed: Vec<_> = ac
+                .stream_find_iter(log.as_bytes())
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(streamed, expected);
+
+            let trickled: Vec<_> = ac
+                .stream_find_iter(Trickle(log.as_bytes()))
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(trickled, expected);
+        }
+    }
+
+    #[test]
+    fn stream_bounded() {
+        // 长段无匹配的输入，部分匹配一直延续
+        let len = 256 << 10;
+        let patterns = ["error", "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxy", "xx"];
+        let longest = patterns.iter().map(|p| p.len()).max().unwrap();
+
+        for kind in [
+            MatchKind::Overlapping,
+            MatchKind::LeftmostFirst,
+            MatchKind::LeftmostLongest,
+        ] {
+            let ac = AhoCorasick::new(&patterns[..2], kind);
+            let mut iter = ac.stream_find_iter(io::repeat(b'x').take(len));
+            assert!(iter.next().is_none());
+            assert!(iter.stream.buf.len() <= 2 * CHUNK + longest, "{kind:?}");
+
+            // 匹配密集时同样有界
+            let ac = AhoCorasick::new(patterns, kind);
+            let mut iter = ac.stream_find_iter(io::repeat(b'x').take(len));
+            let count = iter.by_ref().take_while(Result::is_ok).count();
+            assert!(count >= len as usize / 2);
+            assert!(iter.stream.buf.len() <= 2 * CHUNK + longest, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn stream_error() {
+        struct Broken;
+
+        impl Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+        }
+
+        let ac = AhoCorasick::new(["x"], MatchKind::Overlapping);
+        let mut iter = ac.stream_find_iter(Broken);
+
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,117 @@
+use std::mem;
+
+// 插入、删除、替换各计一次的最少编辑次数，O(nm)时间，O(min(n, m))空间
+pub fn levenshtein<T: Eq>(a: &[T], b: &[T]) -> usize {
+    // 让b为较短者，只保留两行
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for (i, x) in a.iter().enumerate() {
+        curr[0] = i + 1;
+
+        for (j, y) in b.iter().enumerate() {
+            let replace = prev[j] + usize::from(x != y);
+            curr[j + 1] = replace.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+
+        mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+// 在Levenshtein的基础上，交换相邻两个元素也只计一次
+// 不限制交换后再编辑（如"ca" -> "abc"为2），需要保留整张表，O(nm)空间
+pub fn damerau_levenshtein<T: Eq>(a: &[T], b: &[T]) -> usize {
+    let (n, m) = (a.len(), b.len());
+    let inf = n + m;
+
+    // d[i + 1][j + 1]为a[..i]与b[..j]的距离，第0行、第0列作哨兵
+    let mut d = vec![vec![inf; m + 2]; n + 2];
+    for i in 0..=n {
+        d[i + 1][1] = i;
+    }
+    for j in 0..=m {
+        d[1][j + 1] = j;
+    }
+
+    // last_row[j]：a[..i]中最后一个等于b[j - 1]的位置（从1计），没有时为0
+    // 通常以元素为键查表，这里逐行更新，只需T: Eq
+    let mut last_row = vec![0; m + 1];
+
+    for i in 1..=n {
+        // 本行中最后一个与a[i - 1]相等的b的位置
+        let mut last_col = 0;
+
+        for j in 1..=m {
+            let (k, l) = (last_row[j], last_col);
+            let cost = if a[i - 1] == b[j - 1] {
+                last_col = j;
+                0
+            } else {
+                1
+            };
+
+            d[i + 1][j + 1] = (d[i][j] + cost)
+                .min(d[i + 1][j] + 1)
+                .min(d[i][j + 1] + 1)
+                // a[k - 1]与a[i - 1]之间删除，b[l - 1]与b[j - 1]之间插入，再交换一次
+                .min(d[k][l] + (i - k - 1) + 1 + (j - l - 1));
+        }
+
+        for j in 1..=m {
+            if a[i - 1] == b[j - 1] {
+                last_row[j] = i;
+            }
+        }
+    }
+
+    d[n + 1][m + 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        assert_eq!(levenshtein(b"kitten", b"sitting"), 3);
+        assert_eq!(levenshtein(b"", b"abc"), 3);
+        assert_eq!(levenshtein(b"flaw", b"lawn"), 2);
+        assert_eq!(levenshtein::<u8>(&[], &[]), 0);
+
+        assert_eq!(damerau_levenshtein(b"ca", b"abc"), 2);
+        assert_eq!(damerau_levenshtein(b"abcdef", b"abdcef"), 1);
+        assert_eq!(damerau_levenshtein(b"", b"ab"), 2);
+        assert_eq!(damerau_levenshtein(b"kitten", b"sitting"), 3);
+    }
+
+    #[test]
+    fn properties() {
+        let words: [&[u8]; 8] = [b"", b"a", b"ab", b"ba", b"abc", b"acb", b"bca", b"aabb"];
+
+        for a in words {
+            for b in words {
+                let lev = levenshtein(a, b);
+                let dl = damerau_levenshtein(a, b);
+
+                assert_eq!(lev, levenshtein(b, a));
+                assert_eq!(dl, damerau_levenshtein(b, a));
+                assert!(dl <= lev);
+                assert!(lev <= a.len().max(b.len()));
+                assert_eq!(lev == 0, a == b);
+            }
+        }
+    }
+
+    #[test]
+    fn generic_elements() {
+        let a = ["let", "x", "=", "1"];
+        let b = ["let", "=", "x", "1", ";"];
+
+        assert_eq!(levenshtein(&a, &b), 3);
+        assert_eq!(damerau_levenshtein(&a, &b), 2);
+    }
+}
@@ -0,0 +1,65 @@
+// Boyer-Moore-Horspool：自右向左比较窗口，按窗口末元素决定右移距离
+// 末元素在模式串（不含最后一位）中最右的出现位置决定位移，
+// 这里从后往前扫描模式串来找它，扫描的次数恰为位移的距离，
+// 因而不需要以元素为键的位移表，只要求T: Eq
+pub fn horspool<'a, T: Eq>(text: &'a [T], pattern: &'a [T]) -> HorspoolMatches<'a, T> {
+    HorspoolMatches {
+        text,
+        pattern,
+        pos: 0,
+    }
+}
+
+pub struct HorspoolMatches<'a, T> {
+    text: &'a [T],
+    pattern: &'a [T],
+    // 当前窗口的起点
+    pos: usize,
+}
+
+impl<T: Eq> Iterator for HorspoolMatches<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let m = self.pattern.len();
+
+        if m == 0 {
+            return super::empty_match(&mut self.pos, self.text.len());
+        }
+
+        while self.pos + m <= self.text.len() {
+            let start = self.pos;
+            let window = &self.text[start..start + m];
+            let matched = window.iter().rev().eq(self.pattern.iter().rev());
+
+            let last = &window[m - 1];
+            self.pos += self.pattern[..m - 1]
+                .iter()
+                .rev()
+                .position(|c| c == last)
+                .map_or(m, |i| i + 1);
+
+            if matched {
+                return Some(start);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::horspool;
+
+    #[test]
+    fn matches() {
+        let found: Vec<_> = horspool(b"here is a simple example", b"example").collect();
+        assert_eq!(found, [17]);
+
+        let found: Vec<_> = horspool(b"aaaaa", b"aa").collect();
+        assert_eq!(found, [0, 1, 2, 3]);
+
+        assert_eq!(horspool(b"ab", b"abc").count(), 0);
+    }
+}
@@ -0,0 +1,93 @@
+// pi[i]为s[..=i]最长的相等真前缀与真后缀的长度
+pub fn prefix_function<T: Eq>(s: &[T]) -> Vec<usize> {
+    let mut pi = vec![0; s.len()];
+
+    for i in 1..s.len() {
+        let mut k = pi[i - 1];
+
+        while k > 0 && s[i] != s[k] {
+            k = pi[k - 1];
+        }
+
+        if s[i] == s[k] {
+            k += 1;
+        }
+
+        pi[i] = k;
+    }
+
+    pi
+}
+
+// Knuth-Morris-Pratt：失配时按前缀函数回退模式串，文本指针从不后退
+pub fn kmp<'a, T: Eq>(text: &'a [T], pattern: &'a [T]) -> KmpMatches<'a, T> {
+    KmpMatches {
+        text,
+        pattern,
+        pi: prefix_function(pattern),
+        pos: 0,
+        matched: 0,
+    }
+}
+
+pub struct KmpMatches<'a, T> {
+    text: &'a [T],
+    pattern: &'a [T],
+    pi: Vec<usize>,
+    pos: usize,
+    // 已与模式串前缀匹配的长度
+    matched: usize,
+}
+
+impl<T: Eq> Iterator for KmpMatches<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let m = self.pattern.len();
+
+        if m == 0 {
+            return super::empty_match(&mut self.pos, self.text.len());
+        }
+
+        while self.pos < self.text.len() {
+            let c = &self.text[self.pos];
+            self.pos += 1;
+
+            while self.matched > 0 && *c != self.pattern[self.matched] {
+                self.matched = self.pi[self.matched - 1];
+            }
+
+            if *c == self.pattern[self.matched] {
+                self.matched += 1;
+            }
+
+            if self.matched == m {
+                self.matched = self.pi[m - 1];
+                return Some(self.pos - m);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{kmp, prefix_function};
+
+    #[test]
+    fn prefix() {
+        assert_eq!(prefix_function(b"abacaba"), [0, 0, 1, 0, 1, 2, 3]);
+        assert_eq!(prefix_function(b"aaaa"), [0, 1, 2, 3]);
+        assert!(prefix_function::<u8>(&[]).is_empty());
+    }
+
+    #[test]
+    fn matches() {
+        let found: Vec<_> = kmp(b"abababa", b"aba").collect();
+        assert_eq!(found, [0, 2, 4]);
+
+        let found: Vec<_> = kmp(&[1, 2, 3], &[4]).collect();
+        assert!(found.is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+mod aho_corasick;
+mod edit_distance;
+mod horspool;
+mod kmp;
+mod myers;
+mod rabin_karp;
+mod suffix_array;
+mod z;
+
+pub use self::aho_corasick::{AhoCorasick, FindIter, Match, MatchKind, StreamFindIter};
+pub use self::edit_distance::{damerau_levenshtein, levenshtein};
+pub use self::horspool::{HorspoolMatches, horspool};
+pub use self::kmp::{KmpMatches, kmp, prefix_function};
+pub use self::myers::{FuzzyMatch, FuzzyMatches, fuzzy_find, myers_distance};
+pub use self::rabin_karp::{RabinKarpMatches, rabin_karp};
+pub use self::suffix_array::{SuffixArray, lcp_array, suffix_array_doubling, suffix_array_sais};
+pub use self::z::{ZMatches, z_function, z_search};
+
+// 单模式的精确匹配都给出所有（可能重叠的）匹配的起点，按升序排列
+// 空模式串在每个位置都匹配，包括文本末尾，即0..=text.len()
+fn empty_match(pos: &mut usize, len: usize) -> Option<usize> {
+    (*pos <= len).then(|| {
+        *pos += 1;
+        *pos - 1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive<T: Eq>(text: &[T], pattern: &[T]) -> Vec<usize> {
+        (0..=text.len())
+            .filter(|&i| text[i..].starts_with(pattern))
+            .collect()
+    }
+
+    // 以三进制展开枚举{0, 1, 2}上所有长度为len的序列
+    fn sequences(len: usize) -> impl Iterator<Item = Vec<u8>> {
+        (0..3usize.pow(len as u32)).map(move |mut code| {
+            (0..len)
+                .map(|_| {
+                    let c = (code % 3) as u8;
+                    code /= 3;
+                    c
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn against_naive() {
+        for n in 0..=7 {
+            for text in sequences(n) {
+                for m in 0..=4 {
+                    for pattern in sequences(m) {
+                        let expected = naive(&text, &pattern);
+
+                        assert_eq!(kmp(&text, &pattern).collect::<Vec<_>>(), expected);
+                        assert_eq!(horspool(&text, &pattern).collect::<Vec<_>>(), expected);
+                        assert_eq!(rabin_karp(&text, &pattern).collect::<Vec<_>>(), expected);
+                        assert_eq!(z_search(&text, &pattern).collect::<Vec<_>>(), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_pattern() {
+        let text = [1, 2, 3];
+
+        assert_eq!(kmp(&text, &[]).collect::<Vec<_>>(), [0, 1, 2, 3]);
+        assert_eq!(horspool(&[] as &[u8], &[]).collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn non_hash_elements() {
+        // 只实现了Eq的元素
+        #[derive(PartialEq, Eq)]
+        struct Token(&'static str);
+        let text = [Token("a"), Token("b"), Token("a")];
+        assert_eq!(horspool(&text, &[Token("a")]).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(z_search(&text, &[Token("b")]).collect::<Vec<_>>(), [1]);
+        assert_eq!(kmp(&text, &[Token("a")]).count(), 2);
+    }
+}
@@ -0,0 +1,292 @@
+// Myers的位并行算法：把动态规划表的一列编码为竖向差分的比特向量，
+// 每个文本元素只需常数次字运算，模式串至多64个元素
+const WORD: usize = u64::BITS as usize;
+
+// 模式串中互不相同的元素及其出现位置的掩码
+// 只要求T: Eq，按元素查掩码时线性扫描，至多64个
+struct Peq<'a, T> {
+    symbols: Vec<(&'a T, u64)>,
+}
+
+impl<'a, T: Eq> Peq<'a, T> {
+    fn new(pattern: impl Iterator<Item = &'a T>) -> Self {
+        let mut symbols: Vec<(&T, u64)> = Vec::new();
+
+        for (i, c) in pattern.enumerate() {
+            match symbols.iter_mut().find(|(s, _)| *s == c) {
+                Some((_, mask)) => *mask |= 1 << i,
+                None => symbols.push((c, 1 << i)),
+            }
+        }
+
+        Self { symbols }
+    }
+
+    fn mask(&self, c: &T) -> u64 {
+        self.symbols
+            .iter()
+            .find(|(s, _)| *s == c)
+            .map_or(0, |&(_, mask)| mask)
+    }
+}
+
+// 一列的状态：pv/mv为各行相对上一行+1/-1，score为末行的值
+struct Column {
+    pv: u64,
+    mv: u64,
+    score: usize,
+    high: u64,
+}
+
+impl Column {
+    fn new(m: usize) -> Self {
+        Self {
+            pv: !0,
+            mv: 0,
+            score: m,
+            high: 1 << (m - 1),
+        }
+    }
+
+    // global为真时首行为0, 1, 2, ...，即整段文本参与比较；否则首行全为0，匹配可从任意位置开始
+    fn step(&mut self, eq: u64, global: bool) {
+        let xv = eq | self.mv;
+        let xh = ((eq & self.pv).wrapping_add(self.pv) ^ self.pv) | eq;
+        let mut ph = self.mv | !(xh | self.pv);
+        let mut mh = self.pv & xh;
+
+        if ph & self.high != 0 {
+            self.score += 1;
+        } else if mh & self.high != 0 {
+            self.score -= 1;
+        }
+
+        ph = (ph << 1) | u64::from(global);
+        mh <<= 1;
+        self.pv = mh | !(xv | ph);
+        self.mv = ph & xv;
+    }
+}
+
+// pattern与text的Levenshtein距离
+pub fn myers_distance<T: Eq>(pattern: &[T], text: &[T]) -> usize {
+    let m = pattern.len();
+    assert!(m <= WORD, "pattern longer than {WORD}");
+
+    if m == 0 {
+        return text.len();
+    }
+
+    let peq = Peq::new(pattern.iter());
+    let mut column = Column::new(m);
+
+    for c in text {
+        column.step(peq.mask(c), true);
+    }
+
+    column.score
+}
+
+// text[start..end]与pattern的编辑距离为errors，且是以end结尾的子串中最小的
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FuzzyMatch {
+    pub start: usize,
+    pub end: usize,
+    pub errors: usize,
+}
+
+// 找出所有结束位置，使得以其结尾的某个子串与pattern的编辑距离不超过max_errors
+// 相邻的结束位置常常同属一处近似出现，均会报告
+pub fn fuzzy_find<'a, T: Eq>(
+    text: &'a [T],
+    pattern: &'a [T],
+    max_errors: usize,
+) -> FuzzyMatches<'a, T> {
+    let m = pattern.len();
+    assert!(m <= WORD, "pattern longer than {WORD}");
+
+    FuzzyMatches {
+        text,
+        pattern,
+        max_errors,
+        forward: Peq::new(pattern.iter()),
+        backward: Peq::new(pattern.iter().rev()),
+        column: (m > 0).then(|| Column::new(m)),
+        end: 0,
+    }
+}
+
+pub struct FuzzyMatches<'a, T> {
+    text: &'a [T],
+    pattern: &'a [T],
+    max_errors: usize,
+    forward: Peq<'a, T>,
+    // 反转的模式串，用来倒着找起点
+    backward: Peq<'a, T>,
+    // 空模式串时为None
+    column: Option<Column>,
+    // 下一个待检查的结束位置
+    end: usize,
+}
+
+impl<T: Eq> FuzzyMatches<'_, T> {
+    // 自end向左逐个延长子串，首次达到errors时即为最短的那个
+    fn start(&self, end: usize, errors: usize) -> usize {
+        let m = self.pattern.len();
+        let mut column = Column::new(m);
+
+        if column.score == errors {
+            return end;
+        }
+
+        for start in (0..end).rev() {
+            column.step(self.backward.mask(&self.text[start]), true);
+
+            if column.score == errors {
+                return start;
+            }
+        }
+
+        0
+    }
+}
+
+impl<T: Eq> Iterator for FuzzyMatches<'_, T> {
+    type Item = FuzzyMatch;
+
+    fn next(&mut self) -> Option<FuzzyMatch> {
+        while self.end <= self.text.len() {
+            let end = self.end;
+            self.end += 1;
+
+            let Some(column) = &mut self.column else {
+                return Some(FuzzyMatch {
+                    start: end,
+                    end,
+                    errors: 0,
+                });
+            };
+
+            if end > 0 {
+                column.step(self.forward.mask(&self.text[end - 1]), false);
+            }
+
+            let errors = column.score;
+            if errors <= self.max_errors {
+                return Some(FuzzyMatch {
+                    start: self.start(end, errors),
+                    end,
+                    errors,
+                });
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::levenshtein;
+
+    fn sequences(len: usize) -> impl Iterator<Item = Vec<u8>> {
+        (0..3usize.pow(len as u32)).map(move |mut code| {
+            (0..len)
+                .map(|_| {
+                    let c = b"abc"[code % 3];
+                    code /= 3;
+                    c
+                })
+                .collect()
+        })
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(myers_distance(b"kitten", b"sitting"), 3);
+        assert_eq!(myers_distance(b"", b"abc"), 3);
+        assert_eq!(myers_distance(b"abc", b""), 3);
+
+        for n in 0..=5 {
+            for m in 0..=4 {
+                for a in sequences(m) {
+                    for b in sequences(n) {
+                        assert_eq!(myers_distance(&a, &b), levenshtein(&a, &b), "{a:?} {b:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn full_word() {
+        let pattern: Vec<u32> = (0..64).map(|i| i % 7).collect();
+        let mut text = pattern.clone();
+        text.remove(30);
+        text.insert(50, 99);
+        text[5] = 42;
+
+        assert_eq!(
+            myers_distance(&pattern, &text),
+            levenshtein(&pattern, &text)
+        );
+        assert_eq!(myers_distance(&pattern, &text), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_long() {
+        myers_distance(&[0; 65], &[0]);
+    }
+
+    #[test]
+    fn identifiers() {
+        let text = b"let reciever = recv_buffer.reciver();";
+        let found: Vec<_> = fuzzy_find(text, b"receiver", 2)
+            .map(|m| (&text[m.start..m.end], m.errors))
+            .collect();
+
+        // 调换两个元素在Levenshtein距离下记为2
+        assert!(found.contains(&(&b"reciever"[..], 2)));
+        assert!(found.contains(&(&b"reciver"[..], 1)));
+        assert!(found.iter().all(|&(_, errors)| errors <= 2));
+
+        let exact: Vec<_> = fuzzy_find(text, b"recv", 0).map(|m| m.start).collect();
+        assert_eq!(exact, [15]);
+    }
+
+    #[test]
+    fn against_naive() {
+        for n in 0..=6 {
+            for text in sequences(n) {
+                for m in 0..=3 {
+                    for pattern in sequences(m) {
+                        for k in 0..=2 {
+                            let found: Vec<_> = fuzzy_find(&text, &pattern, k).collect();
+
+                            // 以end结尾的子串中最小的编辑距离，及取到它的最短子串
+                            let expected: Vec<_> = (0..=n)
+                                .filter_map(|end| {
+                                    let (errors, start) = (0..=end)
+                                        .map(|s| {
+                                            (levenshtein(&pattern, &text[s..end]), usize::MAX - s)
+                                        })
+                                        .min()
+                                        .unwrap();
+                                    (errors <= k).then_some(FuzzyMatch {
+                                        start: usize::MAX - start,
+                                        end,
+                                        errors,
+                                    })
+                                })
+                                .collect();
+
+                            assert_eq!(found, expected, "{text:?} {pattern:?} {k}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash};
+
+// 模数取梅森素数2⁶¹ - 1
+const MODULUS: u64 = (1 << 61) - 1;
+const BASE: u64 = 1_000_003;
+
+// Rabin-Karp：窗口的多项式哈希可以O(1)滚动更新，哈希相等时再逐个比较
+// 元素先经Hash映射为整数，因此要求T: Hash
+pub fn rabin_karp<'a, T: Eq + Hash>(text: &'a [T], pattern: &'a [T]) -> RabinKarpMatches<'a, T> {
+    let m = pattern.len();
+    let hash = |s: &[T]| s.iter().fold(0, |h, c| add(mul(h, BASE), element(c)));
+
+    RabinKarpMatches {
+        text,
+        pattern,
+        pos: 0,
+        target: hash(pattern),
+        window: if m <= text.len() { hash(&text[..m]) } else { 0 },
+        // BASE^(m - 1)，滚动时移出首元素用
+        lead: (1..m).fold(1, |p, _| mul(p, BASE)),
+    }
+}
+
+pub struct RabinKarpMatches<'a, T> {
+    text: &'a [T],
+    pattern: &'a [T],
+    pos: usize,
+    target: u64,
+    // text[pos..pos + m]的哈希
+    window: u64,
+    lead: u64,
+}
+
+impl<T: Eq + Hash> Iterator for RabinKarpMatches<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let m = self.pattern.len();
+        let n = self.text.len();
+
+        if m == 0 {
+            return super::empty_match(&mut self.pos, n);
+        }
+
+        while self.pos + m <= n {
+            let start = self.pos;
+            let matched =
+                self.window == self.target && self.text[start..start + m] == *self.pattern;
+
+            if start + m < n {
+                let out = mul(element(&self.text[start]), self.lead);
+                let rest = add(self.window, MODULUS - out);
+                self.window = add(mul(rest, BASE), element(&self.text[start + m]));
+            }
+            self.pos += 1;
+
+            if matched {
+                return Some(start);
+            }
+        }
+
+        None
+    }
+}
+
+fn element<T: Hash>(c: &T) -> u64 {
+    BuildHasherDefault::<DefaultHasher>::default().hash_one(c) % MODULUS
+}
+
+fn add(a: u64, b: u64) -> u64 {
+    (a + b) % MODULUS
+}
+
+fn mul(a: u64, b: u64) -> u64 {
+    ((a as u128 * b as u128) % MODULUS as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rabin_karp;
+
+    #[test]
+    fn matches() {
+        let found: Vec<_> = rabin_karp(b"abracadabra", b"abra").collect();
+        assert_eq!(found, [0, 7]);
+
+        let words = ["to", "be", "or", "not", "to", "be"];
+        let found: Vec<_> = rabin_karp(&words, &["to", "be"]).collect();
+        assert_eq!(found, [0, 4]);
+
+        assert_eq!(rabin_karp(b"a", b"ab").count(), 0);
+    }
+}
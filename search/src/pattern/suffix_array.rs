@@ -0,0 +1,346 @@
+use std::cmp::Ordering;
+use std::mem;
+use std::ops::Range;
+
+use crate::{binary_search_by, equal_range_by, lower_bound};
+
+const EMPTY: usize = usize::MAX;
+
+// 文本的所有后缀按字典序排列后的起点
+pub struct SuffixArray<'a, T> {
+    text: &'a [T],
+    sa: Vec<usize>,
+}
+
+impl<'a, T: Ord> SuffixArray<'a, T> {
+    pub fn new(text: &'a [T]) -> Self {
+        Self {
+            text,
+            sa: suffix_array_sais(text),
+        }
+    }
+
+    pub fn text(&self) -> &'a [T] {
+        self.text
+    }
+
+    pub fn suffixes(&self) -> &[usize] {
+        &self.sa
+    }
+
+    pub fn lcp(&self) -> Vec<usize> {
+        lcp_array(self.text, &self.sa)
+    }
+
+    pub fn contains(&self, pattern: &[T]) -> bool {
+        binary_search_by(&self.sa, |&i| self.compare(i, pattern)).is_ok()
+    }
+
+    pub fn count(&self, pattern: &[T]) -> usize {
+        self.range(pattern).len()
+    }
+
+    // 所有出现位置，按对应后缀的字典序而非位置排列
+    pub fn locate(&self, pattern: &[T]) -> &[usize] {
+        &self.sa[self.range(pattern)]
+    }
+
+    // 以pattern为前缀的后缀在后缀数组中连续排列
+    fn range(&self, pattern: &[T]) -> Range<usize> {
+        equal_range_by(&self.sa, |&i| self.compare(i, pattern))
+    }
+
+    // 后缀i以pattern开头时视为相等
+    fn compare(&self, i: usize, pattern: &[T]) -> Ordering {
+        let suffix = &self.text[i..];
+        suffix[..suffix.len().min(pattern.len())].cmp(pattern)
+    }
+}
+
+// 倍增：按前k个元素的排名，对(rank[i], rank[i + k])排序得到前2k个元素的排名
+// 每轮一次比较排序，O(n log² n)
+pub fn suffix_array_doubling<T: Ord>(text: &[T]) -> Vec<usize> {
+    let n = text.len();
+    let mut sa: Vec<usize> = (0..n).collect();
+    sa.sort_by(|&a, &b| text[a].cmp(&text[b]));
+
+    let mut rank = vec![0; n];
+    for i in 1..n {
+        rank[sa[i]] = rank[sa[i - 1]] + usize::from(text[sa[i - 1]] != text[sa[i]]);
+    }
+
+    let mut next = vec![0; n];
+    let mut k = 1;
+
+    // 排名互不相同时已排好
+    while k < n && rank[sa[n - 1]] < n - 1 {
+        // 不足2k个元素的后缀，第二关键字最小
+        let key = |i: usize| (rank[i], rank.get(i + k).copied());
+        sa.sort_unstable_by_key(|&i| key(i));
+
+        for i in 1..n {
+            next[sa[i]] = next[sa[i - 1]] + usize::from(key(sa[i - 1]) != key(sa[i]));
+        }
+
+        mem::swap(&mut rank, &mut next);
+        k *= 2;
+    }
+
+    sa
+}
+
+// SA-IS：由LMS子串的次序诱导出全体后缀的次序，O(n)
+pub fn suffix_array_sais<T: Ord>(text: &[T]) -> Vec<usize> {
+    // 先把元素压缩为0..alphabet.len()的整数
+    let mut alphabet: Vec<&T> = text.iter().collect();
+    alphabet.sort_unstable();
+    alphabet.dedup();
+
+    let s: Vec<usize> = text.iter().map(|c| lower_bound(&alphabet, c)).collect();
+    sa_is(&s, alphabet.len().saturating_sub(1))
+}
+
+// s的元素都在0..=upper内
+fn sa_is(s: &[usize], upper: usize) -> Vec<usize> {
+    let n = s.len();
+
+    match n {
+        0 => return vec![],
+        1 => return vec![0],
+        2 => return if s[0] < s[1] { vec![0, 1] } else { vec![1, 0] },
+        _ => {}
+    }
+
+    // S型：后缀小于其后一个后缀；最后一个后缀视为L型
+    let mut stype = vec![false; n];
+    for i in (0..n - 1).rev() {
+        stype[i] = if s[i] == s[i + 1] {
+            stype[i + 1]
+        } else {
+            s[i] < s[i + 1]
+        };
+    }
+
+    // 每个桶中L型在前、S型在后，分别记下两部分的起点
+    let mut l_start = vec![0; upper + 2];
+    let mut s_start = vec![0; upper + 1];
+    for i in 0..n {
+        if stype[i] {
+            l_start[s[i] + 1] += 1;
+        } else {
+            s_start[s[i]] += 1;
+        }
+    }
+    for c in 0..=upper {
+        s_start[c] += l_start[c];
+        l_start[c + 1] += s_start[c];
+    }
+
+    let mut sa = vec![EMPTY; n];
+
+    let induce = |sa: &mut [usize], lms: &[usize]| {
+        sa.fill(EMPTY);
+
+        let mut bucket = s_start.clone();
+        for &p in lms {
+            sa[bucket[s[p]]] = p;
+            bucket[s[p]] += 1;
+        }
+
+        // 自左向右放L型
+        let mut bucket = l_start.clone();
+        sa[bucket[s[n - 1]]] = n - 1;
+        bucket[s[n - 1]] += 1;
+        for i in 0..n {
+            let p = sa[i];
+            if p != EMPTY && p >= 1 && !stype[p - 1] {
+                sa[bucket[s[p - 1]]] = p - 1;
+                bucket[s[p - 1]] += 1;
+            }
+        }
+
+        // 自右向左放S型，从各桶末尾往前填
+        let mut bucket = l_start.clone();
+        for i in (0..n).rev() {
+            let p = sa[i];
+            if p != EMPTY && p >= 1 && stype[p - 1] {
+                bucket[s[p - 1] + 1] -= 1;
+                sa[bucket[s[p - 1] + 1]] = p - 1;
+            }
+        }
+    };
+
+    // LMS：左侧为L型的S型位置
+    let is_lms = |i: usize| i > 0 && !stype[i - 1] && stype[i];
+    let lms: Vec<usize> = (1..n).filter(|&i| is_lms(i)).collect();
+    let mut lms_index = vec![EMPTY; n];
+    for (k, &p) in lms.iter().enumerate() {
+        lms_index[p] = k;
+    }
+
+    induce(&mut sa, &lms);
+
+    let m = lms.len();
+    if m > 0 {
+        // 诱导排序后LMS子串已有序，相同的子串编号相同
+        let sorted: Vec<usize> = sa.iter().copied().filter(|&p| is_lms(p)).collect();
+        let end = |p: usize| lms.get(lms_index[p] + 1).copied().unwrap_or(n);
+
+        let mut reduced = vec![0; m];
+        let mut name = 0;
+        for w in sorted.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            let (end_a, end_b) = (end(a), end(b));
+            // 以文本末尾结束的子串不与任何子串相同
+            let same =
+                end_a - a == end_b - b && end_a < n && end_b < n && s[a..=end_a] == s[b..=end_b];
+
+            if !same {
+                name += 1;
+            }
+            reduced[lms_index[b]] = name;
+        }
+
+        // 递归求LMS后缀的次序，据此再诱导一次
+        let order: Vec<usize> = sa_is(&reduced, name).into_iter().map(|k| lms[k]).collect();
+        induce(&mut sa, &order);
+    }
+
+    sa
+}
+
+// Kasai：lcp[i]为后缀sa[i - 1]与sa[i]的最长公共前缀，lcp[0] = 0
+// 按文本位置顺序计算，相邻位置的值至多减1，O(n)
+pub fn lcp_array<T: Eq>(text: &[T], sa: &[usize]) -> Vec<usize> {
+    let n = text.len();
+    let mut rank = vec![0; n];
+    for (r, &p) in sa.iter().enumerate() {
+        rank[p] = r;
+    }
+
+    let mut lcp = vec![0; n];
+    let mut h: usize = 0;
+
+    for p in 0..n {
+        if rank[p] == 0 {
+            h = 0;
+            continue;
+        }
+
+        let q = sa[rank[p] - 1];
+        while p + h < n && q + h < n && text[p + h] == text[q + h] {
+            h += 1;
+        }
+
+        lcp[rank[p]] = h;
+        h = h.saturating_sub(1);
+    }
+
+    lcp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern::kmp;
+
+    fn naive(text: &[u8]) -> Vec<usize> {
+        let mut sa: Vec<usize> = (0..text.len()).collect();
+        sa.sort_by_key(|&i| &text[i..]);
+        sa
+    }
+
+    fn naive_lcp(text: &[u8], sa: &[usize]) -> Vec<usize> {
+        (0..sa.len())
+            .map(|r| match r {
+                0 => 0,
+                _ => text[sa[r - 1]..]
+                    .iter()
+                    .zip(&text[sa[r]..])
+                    .take_while(|(a, b)| a == b)
+                    .count(),
+            })
+            .collect()
+    }
+
+    // 斐波那契串：重复结构很多
+    fn fibonacci_word(len: usize) -> Vec<u8> {
+        let (mut a, mut b) = (b"a".to_vec(), b"ab".to_vec());
+        while b.len() < len {
+            (a, b) = (b.clone(), [b, a].concat());
+        }
+        b.truncate(len);
+        b
+    }
+
+    #[test]
+    fn banana() {
+        let sa = SuffixArray::new(b"banana");
+
+        assert_eq!(sa.suffixes(), [5, 3, 1, 0, 4, 2]);
+        assert_eq!(sa.lcp(), [0, 1, 3, 0, 0, 2]);
+        assert_eq!(suffix_array_doubling(b"banana"), [5, 3, 1, 0, 4, 2]);
+    }
+
+    #[test]
+    fn against_naive() {
+        // {a, b, c}上长度不超过8的所有串
+        for len in 0..=8 {
+            for mut code in 0..3usize.pow(len as u32) {
+                let text: Vec<u8> = (0..len)
+                    .map(|_| {
+                        let c = b"abc"[code % 3];
+                        code /= 3;
+                        c
+                    })
+                    .collect();
+
+                let expected = naive(&text);
+                assert_eq!(suffix_array_sais(&text), expected, "{text:?}");
+                assert_eq!(suffix_array_doubling(&text), expected, "{text:?}");
+                assert_eq!(
+                    lcp_array(&text, &expected),
+                    naive_lcp(&text, &expected),
+                    "{text:?}"
+                );
+            }
+        }
+
+        for text in [
+            fibonacci_word(3000),
+            b"mississippi".repeat(200),
+            (0..4000u32).map(|i| (i * i % 251) as u8).collect(),
+        ] {
+            let expected = naive(&text);
+            assert_eq!(suffix_array_sais(&text), expected);
+            assert_eq!(suffix_array_doubling(&text), expected);
+        }
+    }
+
+    #[test]
+    fn generic_elements() {
+        let words = ["to", "be", "or", "not", "to", "be"];
+        let sa = SuffixArray::new(&words);
+
+        assert_eq!(sa.suffixes(), suffix_array_doubling(&words));
+        assert_eq!(sa.count(&["to", "be"]), 2);
+        assert!(sa.contains(&["or", "not"]));
+        assert!(!sa.contains(&["be", "to"]));
+    }
+
+    #[test]
+    fn queries() {
+        let text = fibonacci_word(2000);
+        let sa = SuffixArray::new(&text);
+
+        for pattern in [&b"a"[..], b"ab", b"aba", b"abaab", b"bb", b"babaabab", b""] {
+            let mut located = sa.locate(pattern).to_vec();
+            located.sort_unstable();
+            let expected: Vec<usize> = kmp(&text, pattern).filter(|&i| i < text.len()).collect();
+
+            assert_eq!(located, expected, "{pattern:?}");
+            assert_eq!(sa.count(pattern), expected.len());
+            assert_eq!(sa.contains(pattern), !expected.is_empty());
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// z[i]为s与s[i..]的最长公共前缀的长度，约定z[0] = s.len()
+pub fn z_function<T: Eq>(s: &[T]) -> Vec<usize> {
+    let n = s.len();
+    let mut z = vec![0; n];
+    // s[l..r]与s[..r - l]相同，r为已知的最远右端
+    let (mut l, mut r) = (0, 0);
+
+    if n > 0 {
+        z[0] = n;
+    }
+
+    for i in 1..n {
+        let mut k = if i < r { z[i - l].min(r - i) } else { 0 };
+
+        while i + k < n && s[k] == s[i + k] {
+            k += 1;
+        }
+
+        if i + k > r {
+            (l, r) = (i, i + k);
+        }
+
+        z[i] = k;
+    }
+
+    z
+}
+
+// 对文本逐位计算与模式串的最长公共前缀，长度达到模式串长即为一次匹配
+// 无需在模式串与文本之间插入分隔符，因而只要求T: Eq
+pub fn z_search<'a, T: Eq>(text: &'a [T], pattern: &'a [T]) -> ZMatches<'a, T> {
+    ZMatches {
+        text,
+        pattern,
+        z: z_function(pattern),
+        pos: 0,
+        l: 0,
+        r: 0,
+    }
+}
+
+pub struct ZMatches<'a, T> {
+    text: &'a [T],
+    pattern: &'a [T],
+    z: Vec<usize>,
+    pos: usize,
+    // text[l..r]与pattern[..r - l]相同
+    l: usize,
+    r: usize,
+}
+
+impl<T: Eq> Iterator for ZMatches<'_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let m = self.pattern.len();
+        let n = self.text.len();
+
+        if m == 0 {
+            return super::empty_match(&mut self.pos, n);
+        }
+
+        while self.pos + m <= n {
+            let i = self.pos;
+            self.pos += 1;
+
+            let mut k = if i < self.r {
+                self.z[i - self.l].min(self.r - i)
+            } else {
+                0
+            };
+
+            while k < m && self.text[i + k] == self.pattern[k] {
+                k += 1;
+            }
+
+            if i + k > self.r {
+                (self.l, self.r) = (i, i + k);
+            }
+
+            if k == m {
+                return Some(i);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{z_function, z_search};
+
+    #[test]
+    fn z() {
+        assert_eq!(z_function(b"aabxaab"), [7, 1, 0, 0, 3, 1, 0]);
+        assert_eq!(z_function(b"aaaaa"), [5, 4, 3, 2, 1]);
+        assert!(z_function::<u8>(&[]).is_empty());
+    }
+
+    #[test]
+    fn matches() {
+        let found: Vec<_> = z_search(b"aabxaabxaab", b"aabxaab").collect();
+        assert_eq!(found, [0, 4]);
+
+        assert_eq!(z_search(b"xyz", b"q").count(), 0);
+    }
+}
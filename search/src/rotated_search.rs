@@ -0,0 +1,91 @@
+use crate::binary_search;
+
+// seq为升序序列循环左移所得，返回原序列首元素所在的下标，即最小元素的位置
+// 元素互不相同时O(log n)，重复元素多时最坏退化为O(n)；空序列返回0
+pub fn find_rotation_point<T: Ord>(seq: &[T]) -> usize {
+    if seq.is_empty() {
+        return 0;
+    }
+
+    // 不变式：旋转点在[low, high]内
+    let mut low = 0;
+    let mut high = seq.len() - 1;
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if seq[mid] > seq[high] {
+            low = mid + 1;
+        } else if seq[mid] < seq[high] {
+            high = mid;
+        } else if seq[high - 1] > seq[high] {
+            // 无法判断旋转点在哪一侧，只能排除high，但high本身可能就是旋转点
+            return high;
+        } else {
+            high -= 1;
+        }
+    }
+
+    low
+}
+
+// 在旋转过的升序序列中查找x，有多个时返回按原序列次序的第一个
+pub fn search_rotated<T: Ord>(seq: &[T], x: &T) -> Option<usize> {
+    let pivot = find_rotation_point(seq);
+    let (tail, head) = seq.split_at(pivot);
+
+    // seq[pivot..]是原序列的前半段
+    binary_search(head, x)
+        .map(|i| pivot + i)
+        .or_else(|_| binary_search(tail, x))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let arr: [u32; 0] = [];
+
+        assert_eq!(find_rotation_point(&arr), 0);
+        assert_eq!(search_rotated(&arr, &1), None);
+    }
+
+    #[test]
+    fn rotation_point() {
+        assert_eq!(find_rotation_point(&[4, 5, 6, 7, 0, 1, 2]), 4);
+        assert_eq!(find_rotation_point(&[1, 2, 3]), 0);
+        assert_eq!(find_rotation_point(&[1, 1, 2, 1]), 3);
+        assert_eq!(find_rotation_point(&[2, 2, 2]), 0);
+    }
+
+    #[test]
+    fn against_linear() {
+        // 含重复元素的各种升序序列的所有旋转
+        for len in 0..12 {
+            for dup in 1..4 {
+                let sorted: Vec<u32> = (0..len).map(|i| i / dup).collect();
+
+                for shift in 0..len.max(1) as usize {
+                    let mut arr = sorted.clone();
+                    arr.rotate_left(shift);
+
+                    let pivot = find_rotation_point(&arr);
+                    assert!(
+                        arr[pivot..].iter().chain(&arr[..pivot]).is_sorted(),
+                        "{arr:?}"
+                    );
+
+                    for x in 0..=len / dup + 1 {
+                        let logical = (0..arr.len()).map(|i| (pivot + i) % arr.len());
+                        let expected = logical.clone().find(|&i| arr[i] == x);
+
+                        assert_eq!(search_rotated(&arr, &x), expected, "{arr:?} {x}");
+                    }
+                }
+            }
+        }
+    }
+}
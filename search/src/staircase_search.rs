@@ -0,0 +1,69 @@
+// matrix的每行、每列都升序，从右上角出发：
+// 当前元素大于x则左移一列，小于x则下移一行，至多走行数 + 列数步
+// 各行须等长，返回某个等于x的元素的(行, 列)
+pub fn staircase_search<T, R>(matrix: &[R], x: &T) -> Option<(usize, usize)>
+where
+    T: Ord,
+    R: AsRef<[T]>,
+{
+    let cols = matrix.first()?.as_ref().len();
+    let mut row = 0;
+    let mut col = cols.checked_sub(1)?;
+
+    while row < matrix.len() {
+        let e = &matrix[row].as_ref()[col];
+
+        if e == x {
+            return Some((row, col));
+        }
+
+        if e > x {
+            col = col.checked_sub(1)?;
+        } else {
+            row += 1;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let rows: [[u32; 0]; 0] = [];
+        assert_eq!(staircase_search(&rows, &1), None);
+
+        let rows: [[u32; 0]; 3] = [[], [], []];
+        assert_eq!(staircase_search(&rows, &1), None);
+    }
+
+    #[test]
+    fn against_linear() {
+        for rows in 1..6 {
+            for cols in 1..6 {
+                let matrix: Vec<Vec<usize>> = (0..rows)
+                    .map(|r| (0..cols).map(|c| r * 2 + c * 3).collect())
+                    .collect();
+
+                for x in 0..30 {
+                    match staircase_search(&matrix, &x) {
+                        Some((r, c)) => assert_eq!(matrix[r][c], x),
+                        None => assert!(matrix.iter().flatten().all(|&e| e != x)),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn slices() {
+        let matrix = [&[1, 4, 7][..], &[2, 5, 8], &[3, 6, 9]];
+
+        assert_eq!(staircase_search(&matrix, &6), Some((2, 1)));
+        assert_eq!(staircase_search(&matrix, &10), None);
+        assert_eq!(staircase_search(&matrix, &0), None);
+    }
+}
@@ -0,0 +1,93 @@
+use std::ops::Range;
+
+use crate::bisection::{Integer, Tolerance, search_first_true};
+
+// f在range上严格单峰：先严格增后严格减，返回最大值所在的位置
+// 整数上无需三分，二分f(x) >= f(x + 1)的分界即可；range不得为空
+pub fn ternary_search<T, K, F>(range: Range<T>, mut f: F) -> T
+where
+    T: Integer,
+    K: Ord,
+    F: FnMut(T) -> K,
+{
+    assert!(range.start < range.end, "empty range");
+
+    let last = range.end;
+    search_first_true(range.start..last, |x| {
+        let next = x.successor();
+        next >= last || f(x) >= f(next)
+    })
+}
+
+// 浮点版本：每轮比较两个三等分点，舍弃较小者外侧的三分之一
+pub fn ternary_search_f64<F>(range: Range<f64>, tolerance: Tolerance, mut f: F) -> f64
+where
+    F: FnMut(f64) -> f64,
+{
+    let Range {
+        start: mut low,
+        end: mut high,
+    } = range;
+
+    for _ in 0..tolerance.max_iterations {
+        if high - low <= tolerance.epsilon {
+            break;
+        }
+
+        let third = (high - low) / 3.0;
+        let (m1, m2) = (low + third, high - third);
+
+        // 精度已到极限
+        if m1 <= low || m2 >= high || m1 >= m2 {
+            break;
+        }
+
+        if f(m1) < f(m2) {
+            low = m1;
+        } else {
+            high = m2;
+        }
+    }
+
+    low + (high - low) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Reverse;
+
+    use super::*;
+
+    #[test]
+    fn integer_peak() {
+        assert_eq!(ternary_search(0..100i64, |x| -(x - 37) * (x - 37)), 37);
+        assert_eq!(ternary_search(-50..50i32, |x| 10 - x.abs()), 0);
+
+        // 单调函数的峰在端点
+        assert_eq!(ternary_search(0..10u32, |x| x), 9);
+        assert_eq!(ternary_search(0..10u32, Reverse), 0);
+        assert_eq!(ternary_search(3..4u32, |x| x), 3);
+    }
+
+    // 用Reverse求最小值
+    #[test]
+    fn integer_valley() {
+        let cost = |x: u64| x.abs_diff(1000) * 3 + 7;
+        assert_eq!(ternary_search(0..5000u64, |x| Reverse(cost(x))), 1000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_range() {
+        ternary_search(0..0u32, |x| x);
+    }
+
+    #[test]
+    fn float_peak() {
+        let x = ternary_search_f64(0.0..4.0, Tolerance::default(), |x| -(x - 1.25) * (x - 1.25));
+        assert!((x - 1.25).abs() < 1e-6);
+
+        let x = ternary_search_f64(0.0..std::f64::consts::PI, Tolerance::default(), f64::sin);
+        assert!((x - std::f64::consts::FRAC_PI_2).abs() < 1e-6);
+    }
+}
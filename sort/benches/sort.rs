@@ -0,0 +1,228 @@
+// 对比各排序算法在不同输入分布、不同规模下的耗时，结果输出为Markdown表格
+//
+// cargo bench -p sort                  全部算法
+// cargo bench -p sort -- qsort msort   只跑名字含qsort或msort的算法
+// SORT_BENCH_SIZES=1000,50000 cargo bench -p sort
+
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// 平方级算法只在不超过此规模时参与
+const QUADRATIC_LIMIT: usize = 10_000;
+
+// 每个单元格至少测这么久，取各轮的中位数
+const BUDGET: Duration = Duration::from_millis(200);
+const MAX_ROUNDS: usize = 25;
+
+const DEFAULT_SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+struct Algorithm {
+    name: &'static str,
+    quadratic: bool,
+    sort: fn(&mut [u32]),
+}
+
+const ALGORITHMS: &[Algorithm] = &[
+    Algorithm {
+        name: "bubble",
+        quadratic: true,
+        sort: sort::bubble,
+    },
+    Algorithm {
+        name: "insertion",
+        quadratic: true,
+        sort: sort::insertion,
+    },
+    Algorithm {
+        name: "selection",
+        quadratic: true,
+        sort: sort::selection,
+    },
+    Algorithm {
+        name: "shell",
+        quadratic: false,
+        sort: sort::shell,
+    },
+    Algorithm {
+        name: "heap",
+        quadratic: false,
+        sort: sort::heap,
+    },
+    Algorithm {
+        name: "msort",
+        quadratic: false,
+        sort: sort::msort,
+    },
+    Algorithm {
+        name: "bottom_up_msort",
+        quadratic: false,
+        sort: sort::bottom_up_msort,
+    },
+    Algorithm {
+        name: "natural_msort",
+        quadratic: false,
+        sort: sort::natural_msort,
+    },
+    Algorithm {
+        name: "timsort",
+        quadratic: false,
+        sort: sort::timsort,
+    },
+    Algorithm {
+        name: "qsort",
+        quadratic: false,
+        sort: sort::qsort,
+    },
+    Algorithm {
+        name: "introsort",
+        quadratic: false,
+        sort: sort::introsort,
+    },
+    Algorithm {
+        name: "pdqsort",
+        quadratic: false,
+        sort: sort::pdqsort,
+    },
+    Algorithm {
+        name: "par_msort",
+        quadratic: false,
+        sort: |arr| sort::par_msort(arr, 1 << 14),
+    },
+    Algorithm {
+        name: "par_qsort",
+        quadratic: false,
+        sort: |arr| sort::par_qsort(arr, 1 << 14),
+    },
+    Algorithm {
+        name: "counting_sort",
+        quadratic: false,
+        sort: sort::counting_sort,
+    },
+    Algorithm {
+        name: "lsd_radix_sort",
+        quadratic: false,
+        sort: sort::lsd_radix_sort,
+    },
+    Algorithm {
+        name: "std stable",
+        quadratic: false,
+        sort: <[u32]>::sort,
+    },
+    Algorithm {
+        name: "std unstable",
+        quadratic: false,
+        sort: <[u32]>::sort_unstable,
+    },
+];
+
+// 生成长度为n的输入
+type Generator = fn(usize, &mut StdRng) -> Vec<u32>;
+
+const DISTRIBUTIONS: &[(&str, Generator)] = &[
+    ("random", |n, rng| {
+        (0..n).map(|_| rng.gen_range(0..n as u32)).collect()
+    }),
+    ("sorted", |n, _| (0..n as u32).collect()),
+    ("reversed", |n, _| (0..n as u32).rev().collect()),
+    ("few-unique", |n, rng| {
+        (0..n).map(|_| rng.gen_range(0..8)).collect()
+    }),
+    ("organ-pipe", |n, _| {
+        let half = (n / 2) as u32;
+        (0..half).chain((0..n as u32 - half).rev()).collect()
+    }),
+    ("sawtooth", |n, _| {
+        let tooth = (n as f64).sqrt() as u32 + 1;
+        (0..n as u32).map(|i| i % tooth).collect()
+    }),
+];
+
+fn main() {
+    // cargo bench会传入--bench等选项，其余参数视为算法名的过滤条件
+    let filters: Vec<String> = env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect();
+    let sizes: Vec<usize> = match env::var("SORT_BENCH_SIZES") {
+        Ok(sizes) => sizes
+            .split(',')
+            .map(|size| {
+                size.trim()
+                    .parse()
+                    .expect("SORT_BENCH_SIZES must be comma-separated integers")
+            })
+            // 空数组无可测，随机生成器也不接受空区间
+            .filter(|&size| size > 0)
+            .collect(),
+        Err(_) => DEFAULT_SIZES.to_vec(),
+    };
+
+    let algorithms: Vec<&Algorithm> = ALGORITHMS
+        .iter()
+        .filter(|algo| filters.is_empty() || filters.iter().any(|f| algo.name.contains(f.as_str())))
+        .collect();
+
+    for size in sizes {
+        println!("\n### n = {size}\n");
+
+        print!("| algorithm |");
+        for (name, _) in DISTRIBUTIONS {
+            print!(" {name} |");
+        }
+        println!();
+        println!("|---|{}", "---:|".repeat(DISTRIBUTIONS.len()));
+
+        for algo in &algorithms {
+            print!("| {} |", algo.name);
+
+            for (_, generate) in DISTRIBUTIONS {
+                if algo.quadratic && size > QUADRATIC_LIMIT {
+                    print!(" - |");
+                    continue;
+                }
+
+                let input = generate(size, &mut StdRng::seed_from_u64(size as u64));
+                print!(" {} |", format_duration(measure(algo.sort, &input)));
+            }
+
+            println!();
+        }
+    }
+}
+
+fn measure(sort: fn(&mut [u32]), input: &[u32]) -> Duration {
+    let mut expected = input.to_vec();
+    expected.sort_unstable();
+
+    let mut times = Vec::new();
+    let start = Instant::now();
+
+    while times.len() < MAX_ROUNDS && (times.len() < 3 || start.elapsed() < BUDGET) {
+        let mut arr = input.to_vec();
+
+        let begin = Instant::now();
+        sort(black_box(&mut arr));
+        times.push(begin.elapsed());
+
+        assert_eq!(arr, expected, "sort produced a wrong result");
+    }
+
+    times.sort_unstable();
+    times[times.len() / 2]
+}
+
+fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+
+    if nanos < 10_000 {
+        format!("{nanos} ns")
+    } else if nanos < 10_000_000 {
+        format!("{:.1} µs", nanos as f64 / 1e3)
+    } else {
+        format!("{:.1} ms", nanos as f64 / 1e6)
+    }
+}
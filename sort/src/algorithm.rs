@@ -0,0 +1,146 @@
+use std::cmp::Ordering;
+
+use crate::bubble::bubble_observed;
+use crate::heap::heap_observed;
+use crate::insertion::insertion_observed;
+use crate::intro::introsort_observed;
+use crate::merge::{
+    bottom_up_msort_observed, msort_observed, natural_msort_observed, timsort_observed,
+};
+use crate::observe::SortObserver;
+use crate::pdq::pdqsort_observed;
+use crate::quick::qsort_observed;
+use crate::selection::selection_observed;
+use crate::shell::{Knuth, shell_observed};
+
+// 以类型表示排序算法，供泛型代码挑选
+pub trait Sort<T> {
+    // 排序的同时向观察者上报每一步操作
+    fn sort_observed<F, O>(arr: &mut [T], compare: F, observer: &mut O)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized;
+
+    fn sort_by<F>(arr: &mut [T], compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        Self::sort_observed(arr, compare, &mut ());
+    }
+
+    fn sort(arr: &mut [T])
+    where
+        T: Ord,
+    {
+        Self::sort_by(arr, T::cmp);
+    }
+
+    fn sort_by_key<K, F>(arr: &mut [T], mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        Self::sort_by(arr, |a, b| f(a).cmp(&f(b)));
+    }
+}
+
+// 稳定排序：相等元素排序后保持原有的相对次序
+pub trait StableSort<T>: Sort<T> {}
+
+pub struct BottomUpMerge;
+pub struct Bubble;
+pub struct Heap;
+pub struct Insertion;
+pub struct Introsort;
+pub struct Merge;
+pub struct NaturalMerge;
+pub struct Pdqsort;
+pub struct Quick;
+pub struct Selection;
+pub struct Shell;
+pub struct Timsort;
+
+macro_rules! impl_sort {
+    ($algo:ident, $observed:expr) => {
+        impl<T> Sort<T> for $algo {
+            fn sort_observed<F, O>(arr: &mut [T], compare: F, observer: &mut O)
+            where
+                F: FnMut(&T, &T) -> Ordering,
+                O: SortObserver + ?Sized,
+            {
+                $observed(arr, compare, observer);
+            }
+        }
+    };
+}
+
+impl_sort!(BottomUpMerge, bottom_up_msort_observed);
+impl_sort!(Bubble, bubble_observed);
+impl_sort!(Heap, heap_observed);
+impl_sort!(Insertion, insertion_observed);
+impl_sort!(Introsort, introsort_observed);
+impl_sort!(Merge, msort_observed);
+impl_sort!(NaturalMerge, natural_msort_observed);
+impl_sort!(Pdqsort, pdqsort_observed);
+impl_sort!(Quick, qsort_observed);
+impl_sort!(Selection, selection_observed);
+impl_sort!(Shell, |arr, compare, observer| {
+    shell_observed(arr, &Knuth, compare, observer)
+});
+impl_sort!(Timsort, timsort_observed);
+
+impl<T> StableSort<T> for BottomUpMerge {}
+impl<T> StableSort<T> for Bubble {}
+impl<T> StableSort<T> for Insertion {}
+impl<T> StableSort<T> for Merge {}
+impl<T> StableSort<T> for NaturalMerge {}
+impl<T> StableSort<T> for Timsort {}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    // 键值范围很小，保证大量重复；标签记录元素的原始下标
+    fn tagged(len: usize) -> Vec<(u8, usize)> {
+        let mut rng = rand::thread_rng();
+        (0..len).map(|tag| (rng.gen_range(0..4), tag)).collect()
+    }
+
+    fn check_sorted<S: Sort<(u8, usize)>>() {
+        for len in 0..64 {
+            let mut arr = tagged(len);
+            S::sort_by_key(&mut arr, |&(key, _)| key);
+            assert!(arr.windows(2).all(|w| w[0].0 <= w[1].0), "{arr:?}");
+        }
+    }
+
+    fn check_stable<S: StableSort<(u8, usize)>>() {
+        for len in 0..64 {
+            let mut arr = tagged(len);
+            S::sort_by_key(&mut arr, |&(key, _)| key);
+            assert!(arr.windows(2).all(|w| w[0] < w[1]), "{arr:?}");
+        }
+    }
+
+    #[test]
+    fn stable() {
+        check_stable::<BottomUpMerge>();
+        check_stable::<Bubble>();
+        check_stable::<Insertion>();
+        check_stable::<Merge>();
+        check_stable::<NaturalMerge>();
+        check_stable::<Timsort>();
+    }
+
+    #[test]
+    fn unstable() {
+        check_sorted::<Heap>();
+        check_sorted::<Introsort>();
+        check_sorted::<Pdqsort>();
+        check_sorted::<Quick>();
+        check_sorted::<Selection>();
+        check_sorted::<Shell>();
+    }
+}
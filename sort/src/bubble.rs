@@ -1,10 +1,31 @@
+use std::cmp::Ordering;
+
+use crate::observe::{Probe, SortObserver};
+
 pub fn bubble<T: Ord>(arr: &mut [T]) {
+    bubble_by(arr, T::cmp);
+}
+
+pub fn bubble_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bubble_observed(arr, compare, &mut ());
+}
+
+pub(crate) fn bubble_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+
     for out in 1..arr.len() {
         let mut swapped = false;
 
         for i in 0..(arr.len() - out) {
-            if arr[i] > arr[i + 1] {
-                arr.swap(i, i + 1);
+            if probe.cmp(arr, i, i + 1, &mut compare) == Ordering::Greater {
+                probe.swap(arr, i, i + 1);
 
                 // 发生了交换
                 swapped = true;
@@ -18,9 +39,17 @@ pub fn bubble<T: Ord>(arr: &mut [T]) {
     }
 }
 
+pub fn bubble_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    bubble_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
 #[cfg(test)]
 mod tests {
-    use super::bubble;
+    use super::{bubble, bubble_by, bubble_by_key};
 
     #[test]
     fn empty() {
@@ -56,4 +85,25 @@ mod tests {
         bubble(&mut arr);
         assert_eq!(arr, [542, 542, 542, 542]);
     }
+
+    #[test]
+    fn reverse() {
+        let mut arr: [char; 4] = ['d', 'a', 'c', 'b'];
+        bubble_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, ['d', 'c', 'b', 'a']);
+    }
+
+    #[test]
+    fn float_key() {
+        let mut arr = [2.5, -1.0, 0.5, 3.0];
+        bubble_by(&mut arr, f64::total_cmp);
+        assert_eq!(arr, [-1.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        bubble_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr, [(1, 'b'), (1, 'd'), (2, 'c'), (3, 'a')]);
+    }
 }
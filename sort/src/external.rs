@@ -0,0 +1,574 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, Write};
+use std::mem;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::vec;
+
+use crate::kmerge::KMerge;
+use crate::msort_by;
+
+// 记录的序列化方式，决定溢出到临时文件时的格式
+pub trait Codec<T> {
+    fn encode<W: Write>(&mut self, item: &T, writer: &mut W) -> io::Result<()>;
+
+    // 读到末尾时返回None
+    fn decode<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<T>>;
+}
+
+// 每行一条记录，记录本身不能含换行符
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Lines;
+
+impl Codec<String> for Lines {
+    fn encode<W: Write>(&mut self, item: &String, writer: &mut W) -> io::Result<()> {
+        // 读回时会被拆成多条记录
+        if item.contains('\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "record contains a newline",
+            ));
+        }
+
+        writer.write_all(item.as_bytes())?;
+        writer.write_all(b"\n")
+    }
+
+    fn decode<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<String>> {
+        let mut line = String::new();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        if line.ends_with('\n') {
+            line.pop();
+        }
+
+        Ok(Some(line))
+    }
+}
+
+// 先写4字节小端长度，再写原始字节
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LengthPrefixed;
+
+impl Codec<Vec<u8>> for LengthPrefixed {
+    fn encode<W: Write>(&mut self, item: &Vec<u8>, writer: &mut W) -> io::Result<()> {
+        let len = u32::try_from(item.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "record too long"))?;
+
+        writer.write_all(&len.to_le_bytes())?;
+        writer.write_all(item)
+    }
+
+    fn decode<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+        if reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+
+        let mut len = [0; 4];
+        reader.read_exact(&mut len)?;
+
+        let mut item = vec![0; u32::from_le_bytes(len) as usize];
+        reader.read_exact(&mut item)?;
+
+        Ok(Some(item))
+    }
+}
+
+// 外部排序：每攒满chunk_len条记录就用msort排好，写入临时文件，
+// 最后用kmerge对所有有序段做多路归并
+// 每次至多归并fan_in段：同一层攒满fan_in段就先归并成一段放到上一层，
+// 因此同时打开的临时文件约为 (fan_in - 1) × 层数，层数是段数以fan_in为底的对数
+#[derive(Debug, Clone)]
+pub struct ExternalSort<C> {
+    codec: C,
+    chunk_len: usize,
+    fan_in: usize,
+    temp_dir: Option<PathBuf>,
+}
+
+impl<C> ExternalSort<C> {
+    pub fn new(codec: C) -> Self {
+        Self {
+            codec,
+            chunk_len: 1 << 16,
+            fan_in: 64,
+            temp_dir: None,
+        }
+    }
+
+    // 内存中最多同时排序的记录数
+    pub fn chunk_len(mut self, chunk_len: usize) -> Self {
+        assert!(chunk_len > 0, "chunk_len must be positive");
+        self.chunk_len = chunk_len;
+        self
+    }
+
+    // 一次最多归并的段数
+    pub fn fan_in(mut self, fan_in: usize) -> Self {
+        assert!(fan_in >= 2, "fan_in must be at least 2");
+        self.fan_in = fan_in;
+        self
+    }
+
+    // 临时文件所在的目录，默认为系统临时目录
+    pub fn temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(dir.into());
+        self
+    }
+
+    pub fn sort<T, I>(self, iter: I) -> io::Result<Sorted<T, C, impl FnMut(&T, &T) -> Ordering>>
+    where
+        T: Ord,
+        C: Codec<T> + Clone,
+        I: IntoIterator<Item = T>,
+    {
+        self.sort_by(iter, T::cmp)
+    }
+
+    pub fn sort_by<T, I, F>(self, iter: I, compare: F) -> io::Result<Sorted<T, C, F>>
+    where
+        C: Codec<T> + Clone,
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.sort_results(iter.into_iter().map(Ok), compare)
+    }
+
+    pub fn sort_by_key<T, I, K, F>(
+        self,
+        iter: I,
+        mut f: F,
+    ) -> io::Result<Sorted<T, C, impl FnMut(&T, &T) -> Ordering>>
+    where
+        C: Codec<T> + Clone,
+        I: IntoIterator<Item = T>,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(iter, move |a, b| f(a).cmp(&f(b)))
+    }
+
+    // 用同一个codec从reader中解码出输入记录
+    pub fn sort_read<T, R>(
+        self,
+        reader: R,
+    ) -> io::Result<Sorted<T, C, impl FnMut(&T, &T) -> Ordering>>
+    where
+        T: Ord,
+        C: Codec<T> + Clone,
+        R: Read,
+    {
+        self.sort_read_by(reader, T::cmp)
+    }
+
+    pub fn sort_read_by<T, R, F>(self, reader: R, compare: F) -> io::Result<Sorted<T, C, F>>
+    where
+        C: Codec<T> + Clone,
+        R: Read,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut codec = self.codec.clone();
+        let mut reader = BufReader::new(reader);
+        let records = std::iter::from_fn(move || codec.decode(&mut reader).transpose());
+
+        self.sort_results(records, compare)
+    }
+
+    fn sort_results<T, I, F>(mut self, records: I, mut compare: F) -> io::Result<Sorted<T, C, F>>
+    where
+        C: Codec<T> + Clone,
+        I: Iterator<Item = io::Result<T>>,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let error = Arc::new(Mutex::new(None));
+        // levels[l]中的段各经过l轮中间归并，层越高的段来自越早的输入
+        let mut levels = Vec::new();
+        let mut chunk = Vec::with_capacity(self.chunk_len);
+
+        for record in records {
+            chunk.push(record?);
+
+            if chunk.len() == self.chunk_len {
+                msort_by(&mut chunk, &mut compare);
+                let run = self.spill(chunk.drain(..), &error)?;
+                self.push_run(&mut levels, run, &mut compare, &error)?;
+            }
+        }
+
+        // 按输入的先后排列，只归并相邻的段，才能保持稳定
+        let mut runs: Vec<_> = levels.into_iter().rev().flatten().collect();
+
+        // 给内存中的最后一段留出位置
+        while runs.len() >= self.fan_in {
+            let newest = runs.split_off(runs.len() - self.fan_in);
+            runs.push(self.merge_runs(newest, &mut compare, &error)?);
+        }
+
+        // 最后一段不必落盘
+        msort_by(&mut chunk, &mut compare);
+        runs.push(Run::Memory(chunk.into_iter()));
+
+        let mut sorted = Sorted {
+            merge: KMerge::new(runs, compare),
+            error,
+            done: false,
+        };
+
+        // 各段的首条记录已在建堆时读出
+        match sorted.take_error() {
+            Some(error) => Err(error),
+            None => Ok(sorted),
+        }
+    }
+
+    // 放入第0层，某层攒满fan_in段时归并成一段放到上一层
+    fn push_run<T, F>(
+        &mut self,
+        levels: &mut Vec<Vec<Run<T, C>>>,
+        mut run: Run<T, C>,
+        compare: &mut F,
+        error: &Arc<Mutex<Option<io::Error>>>,
+    ) -> io::Result<()>
+    where
+        C: Codec<T> + Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let mut level = 0;
+
+        loop {
+            if level == levels.len() {
+                levels.push(Vec::new());
+            }
+
+            levels[level].push(run);
+            if levels[level].len() < self.fan_in {
+                return Ok(());
+            }
+
+            run = self.merge_runs(mem::take(&mut levels[level]), compare, error)?;
+            level += 1;
+        }
+    }
+
+    // 把相邻的若干段归并成一段，写回临时文件
+    fn merge_runs<T, F>(
+        &mut self,
+        runs: Vec<Run<T, C>>,
+        compare: &mut F,
+        error: &Arc<Mutex<Option<io::Error>>>,
+    ) -> io::Result<Run<T, C>>
+    where
+        C: Codec<T> + Clone,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        let run = self.spill(KMerge::new(runs, compare), error)?;
+
+        // 读取这些段时出的错
+        match error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(run),
+        }
+    }
+
+    fn spill<T>(
+        &mut self,
+        items: impl IntoIterator<Item = T>,
+        error: &Arc<Mutex<Option<io::Error>>>,
+    ) -> io::Result<Run<T, C>>
+    where
+        C: Codec<T> + Clone,
+    {
+        // 匿名临时文件，关闭后由系统回收
+        let file = match &self.temp_dir {
+            Some(dir) => tempfile::tempfile_in(dir)?,
+            None => tempfile::tempfile()?,
+        };
+
+        let mut writer = BufWriter::new(file);
+        for item in items {
+            self.codec.encode(&item, &mut writer)?;
+        }
+
+        let mut file = writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        file.rewind()?;
+
+        Ok(Run::Spilled {
+            reader: BufReader::new(file),
+            codec: self.codec.clone(),
+            error: Arc::clone(error),
+        })
+    }
+}
+
+// 一个有序段；解码出错时把错误交给Sorted，自身就此结束
+enum Run<T, C> {
+    Memory(vec::IntoIter<T>),
+    Spilled {
+        reader: BufReader<File>,
+        codec: C,
+        error: Arc<Mutex<Option<io::Error>>>,
+    },
+}
+
+impl<T, C: Codec<T>> Iterator for Run<T, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        match self {
+            Run::Memory(iter) => iter.next(),
+            Run::Spilled {
+                reader,
+                codec,
+                error,
+            } => match codec.decode(reader) {
+                Ok(item) => item,
+                Err(e) => {
+                    *error.lock().unwrap() = Some(e);
+                    None
+                }
+            },
+        }
+    }
+}
+
+// 外部排序的结果，按序产出记录；读取临时文件出错时产出错误并就此结束
+pub struct Sorted<T, C: Codec<T>, F> {
+    merge: KMerge<Run<T, C>, F>,
+    error: Arc<Mutex<Option<io::Error>>>,
+    done: bool,
+}
+
+impl<T, C: Codec<T>, F> Sorted<T, C, F> {
+    fn take_error(&mut self) -> Option<io::Error> {
+        self.error.lock().unwrap().take()
+    }
+}
+
+impl<T, C, F> Iterator for Sorted<T, C, F>
+where
+    C: Codec<T>,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // 补充上一条记录所在的段时出的错
+        if let Some(error) = self.take_error() {
+            self.done = true;
+            return Some(Err(error));
+        }
+
+        let item = self.merge.next();
+        self.done = item.is_none();
+
+        item.map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::io::{self, BufRead, Write};
+    use std::rc::Rc;
+
+    use rand::Rng;
+
+    use super::{Codec, ExternalSort, LengthPrefixed, Lines};
+
+    // 自定义的定长编码
+    #[derive(Clone, Copy)]
+    struct Pair;
+
+    impl Codec<(u32, u32)> for Pair {
+        fn encode<W: Write>(&mut self, &(k, v): &(u32, u32), writer: &mut W) -> io::Result<()> {
+            writer.write_all(&k.to_le_bytes())?;
+            writer.write_all(&v.to_le_bytes())
+        }
+
+        fn decode<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<(u32, u32)>> {
+            if reader.fill_buf()?.is_empty() {
+                return Ok(None);
+            }
+
+            let mut buf = [0; 8];
+            reader.read_exact(&mut buf)?;
+            let k = u32::from_le_bytes(buf[..4].try_into().unwrap());
+            let v = u32::from_le_bytes(buf[4..].try_into().unwrap());
+
+            Ok(Some((k, v)))
+        }
+    }
+
+    // 每个落盘的段各持有一份codec的克隆，借此数出打开的临时文件
+    #[derive(Default)]
+    struct Tracked {
+        open: Rc<Cell<usize>>,
+        peak: Rc<Cell<usize>>,
+        cloned: bool,
+    }
+
+    impl Clone for Tracked {
+        fn clone(&self) -> Self {
+            self.open.set(self.open.get() + 1);
+            self.peak.set(self.peak.get().max(self.open.get()));
+
+            Self {
+                open: Rc::clone(&self.open),
+                peak: Rc::clone(&self.peak),
+                cloned: true,
+            }
+        }
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            if self.cloned {
+                self.open.set(self.open.get() - 1);
+            }
+        }
+    }
+
+    impl Codec<(u32, u32)> for Tracked {
+        fn encode<W: Write>(&mut self, item: &(u32, u32), writer: &mut W) -> io::Result<()> {
+            Pair.encode(item, writer)
+        }
+
+        fn decode<R: BufRead>(&mut self, reader: &mut R) -> io::Result<Option<(u32, u32)>> {
+            Pair.decode(reader)
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let sorted = ExternalSort::new(Lines).sort(Vec::<String>::new()).unwrap();
+        assert_eq!(sorted.count(), 0);
+    }
+
+    #[test]
+    fn in_memory() {
+        let input: Vec<String> = ["pear", "apple", "fig"].map(String::from).to_vec();
+        let sorted: Vec<String> = ExternalSort::new(Lines)
+            .sort(input)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(sorted, ["apple", "fig", "pear"]);
+    }
+
+    #[test]
+    fn spilled() {
+        let mut rng = rand::thread_rng();
+        let input: Vec<Vec<u8>> = (0..5000)
+            .map(|_| {
+                (0..rng.gen_range(0..8))
+                    .map(|_| rng.gen_range(b'a'..=b'e'))
+                    .collect()
+            })
+            .collect();
+        let mut expected = input.clone();
+        expected.sort();
+
+        let sorted: Vec<Vec<u8>> = ExternalSort::new(LengthPrefixed)
+            .chunk_len(97)
+            .temp_dir(std::env::temp_dir())
+            .sort(input)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn stable() {
+        let input: Vec<(u32, u32)> = (0..3000).map(|i| ((i * 7919) % 13, i)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|&(k, _)| k);
+
+        let sorted: Vec<(u32, u32)> = ExternalSort::new(Pair)
+            .chunk_len(100)
+            .sort_by_key(input, |&(k, _)| k)
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn fan_in() {
+        // 200段，以4为底需要4层
+        let input: Vec<(u32, u32)> = (0..2000).map(|i| ((i * 7919) % 31, i)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|&(k, _)| k);
+
+        let codec = Tracked::default();
+        let (open, peak) = (Rc::clone(&codec.open), Rc::clone(&codec.peak));
+        let sorted = ExternalSort::new(codec)
+            .chunk_len(10)
+            .fan_in(4)
+            .sort_by_key(input, |&(k, _)| k)
+            .unwrap();
+
+        // 最后一次归并还有内存中的一段
+        assert!(open.get() <= 3, "{}", open.get());
+        // 每层至多3段，另有正在归并的那一层多出的一段与正在写入的一段
+        assert!(peak.get() <= 3 * 4 + 2, "{}", peak.get());
+
+        let sorted: Vec<(u32, u32)> = sorted.collect::<io::Result<_>>().unwrap();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn from_reader() {
+        let text = "delta\nalpha\necho\ncharlie\nbravo\n";
+        let sorted: Vec<String> = ExternalSort::new(Lines)
+            .chunk_len(2)
+            .sort_read_by(text.as_bytes(), |a: &String, b: &String| b.cmp(a))
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(sorted, ["echo", "delta", "charlie", "bravo", "alpha"]);
+    }
+
+    #[test]
+    fn multiline_record() {
+        let mut buf = Vec::new();
+        let result = Lines.encode(&"a\nb".to_owned(), &mut buf);
+        assert_eq!(
+            result.err().map(|e| e.kind()),
+            Some(io::ErrorKind::InvalidInput)
+        );
+
+        let input = ["c", "a\nb", "d"].map(String::from);
+        let result = ExternalSort::new(Lines).chunk_len(2).sort(input);
+        assert_eq!(
+            result.err().map(|e| e.kind()),
+            Some(io::ErrorKind::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn truncated_input() {
+        // 声明长度为4，实际只有2字节
+        let bytes = [4, 0, 0, 0, 1, 2];
+        let result = ExternalSort::new(LengthPrefixed).sort_read(&bytes[..]);
+
+        assert_eq!(
+            result.err().map(|e| e.kind()),
+            Some(io::ErrorKind::UnexpectedEof)
+        );
+    }
+}
@@ -1,4 +1,12 @@
-fn sink<T: Ord>(arr: &mut [T], mut parent: usize) {
+use std::cmp::Ordering;
+
+use crate::observe::{Probe, SortObserver};
+
+pub(crate) fn sink<T, F, O>(arr: &mut [T], mut parent: usize, compare: &mut F, probe: &mut Probe<O>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     let last = arr.len() - 1;
 
     loop {
@@ -12,14 +20,14 @@ fn sink<T: Ord>(arr: &mut [T], mut parent: usize) {
 
         // 确认左子节点不为最后父节点，
         // 再取最大子节点
-        let max = match left != last && arr[right] > arr[left] {
+        let max = match left != last && probe.cmp(arr, right, left, compare) == Ordering::Greater {
             true => right,
             false => left,
         };
 
         // 将传入的根下沉到终点
-        if arr[parent] < arr[max] {
-            arr.swap(parent, max);
+        if probe.cmp(arr, parent, max, compare) == Ordering::Less {
+            probe.swap(arr, parent, max);
         }
 
         parent = max;
@@ -28,7 +36,40 @@ fn sink<T: Ord>(arr: &mut [T], mut parent: usize) {
     // 局部堆有序了
 }
 
+// 将末端的新节点上浮到合适位置
+pub(crate) fn swim<T, F>(arr: &mut [T], mut child: usize, compare: &mut F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    while child > 0 {
+        let parent = (child - 1) / 2;
+
+        if compare(&arr[parent], &arr[child]) != Ordering::Less {
+            break;
+        }
+
+        arr.swap(parent, child);
+        child = parent;
+    }
+}
+
 pub fn heap<T: Ord>(arr: &mut [T]) {
+    heap_by(arr, T::cmp);
+}
+
+pub fn heap_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    heap_observed(arr, compare, &mut ());
+}
+
+pub(crate) fn heap_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
     let len = arr.len();
 
     if len < 2 {
@@ -38,21 +79,29 @@ pub fn heap<T: Ord>(arr: &mut [T]) {
     // 构建大顶堆
     // 从底向顶遍历所有父节点
     for parent in (0..=len / 2 - 1).rev() {
-        sink(arr, parent);
+        sink(arr, parent, &mut compare, &mut probe);
     }
 
     for end in (1..len).rev() {
         // 释放最大节点至数组末
-        arr.swap(0, end);
+        probe.swap(arr, 0, end);
 
         // 重新堆化
-        sink(&mut arr[..end], 0);
+        sink(&mut arr[..end], 0, &mut compare, &mut probe);
     }
 }
 
+pub fn heap_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    heap_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
 #[cfg(test)]
 mod tests {
-    use super::heap;
+    use super::{heap, heap_by, heap_by_key};
 
     #[test]
     fn empty() {
@@ -69,4 +118,25 @@ mod tests {
 
         assert_eq!(arr, [0, 2, 3, 4, 5, 7, 8, 9, 10, 13, 16]);
     }
+
+    #[test]
+    fn reverse() {
+        let mut arr: [char; 4] = ['d', 'a', 'c', 'b'];
+        heap_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, ['d', 'c', 'b', 'a']);
+    }
+
+    #[test]
+    fn float_key() {
+        let mut arr = [2.5, -1.0, 0.5, 3.0];
+        heap_by(&mut arr, f64::total_cmp);
+        assert_eq!(arr, [-1.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        heap_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr.map(|(k, _)| k), [1, 1, 2, 3]);
+    }
 }
@@ -1,17 +1,46 @@
+use std::cmp::Ordering;
+
+use crate::observe::{Probe, SortObserver};
+
 pub fn insertion<T: Ord>(arr: &mut [T]) {
+    insertion_by(arr, T::cmp);
+}
+
+pub fn insertion_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_observed(arr, compare, &mut ());
+}
+
+pub(crate) fn insertion_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+
     for out in 1..arr.len() {
         let mut n = out;
-        while out > 0 && arr[n] < arr[n - 1] {
-            arr.swap(n, n - 1);
+        while n > 0 && probe.cmp(arr, n, n - 1, &mut compare) == Ordering::Less {
+            probe.swap(arr, n, n - 1);
 
             n -= 1;
         }
     }
 }
 
+pub fn insertion_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    insertion_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
 #[cfg(test)]
 mod tests {
-    use super::insertion;
+    use super::{insertion, insertion_by, insertion_by_key};
 
     #[test]
     fn empty() {
@@ -47,4 +76,25 @@ mod tests {
         insertion(&mut arr);
         assert_eq!(arr, [542, 542, 542, 542]);
     }
+
+    #[test]
+    fn reverse() {
+        let mut arr: [char; 4] = ['d', 'a', 'c', 'b'];
+        insertion_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, ['d', 'c', 'b', 'a']);
+    }
+
+    #[test]
+    fn float_key() {
+        let mut arr = [2.5, -1.0, 0.5, 3.0];
+        insertion_by(&mut arr, f64::total_cmp);
+        assert_eq!(arr, [-1.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        insertion_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr, [(1, 'b'), (1, 'd'), (2, 'c'), (3, 'a')]);
+    }
 }
@@ -0,0 +1,141 @@
+use std::cmp::Ordering;
+
+use crate::heap::heap_observed;
+use crate::insertion::insertion_observed;
+use crate::observe::{Probe, SortObserver};
+use crate::quick::{median_of_three, partition};
+
+// 小于此长度的区间交给插入排序
+const INSERTION_THRESHOLD: usize = 16;
+
+pub fn introsort<T: Ord>(arr: &mut [T]) {
+    introsort_by(arr, T::cmp);
+}
+
+pub fn introsort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    introsort_observed(arr, compare, &mut ());
+}
+
+pub fn introsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    introsort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub(crate) fn introsort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    // 递归深度超过 2⌊log₂n⌋ 即视为快排退化
+    let limit = 2 * arr.len().max(1).ilog2();
+
+    intro(arr, limit, &mut compare, &mut Probe::new(observer));
+}
+
+fn intro<T, F, O>(mut part: &mut [T], mut limit: u32, compare: &mut F, probe: &mut Probe<O>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    loop {
+        let len = part.len();
+
+        if len <= INSERTION_THRESHOLD {
+            insertion_observed(part, &mut *compare, probe);
+            return;
+        }
+
+        // 切分得太差，改用堆排序保住 O(n log n)
+        if limit == 0 {
+            heap_observed(part, &mut *compare, probe);
+            return;
+        }
+        limit -= 1;
+
+        // 三数取中作样本，放到首位
+        let pivot = median_of_three(part, [0, len / 2, len - 1], compare, probe);
+        probe.swap(part, 0, pivot);
+
+        let (lt, gt) = partition(part, compare, probe);
+        let (left, rest) = part.split_at_mut(lt);
+        let right = &mut rest[(gt - lt)..];
+
+        // 递归较短的一侧，较长的一侧留给循环，栈深不超过 log n
+        if left.len() < right.len() {
+            intro(left, limit, compare, &mut probe.at(0));
+            probe.advance(gt);
+            part = right;
+        } else {
+            intro(right, limit, compare, &mut probe.at(gt));
+            part = left;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{introsort, introsort_by, introsort_by_key};
+    use crate::observe::Probe;
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+        introsort(&mut arr);
+    }
+
+    #[test]
+    fn basic() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+        introsort(&mut arr);
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7, 8, 9, 10, 13, 14, 16, 17]);
+    }
+
+    #[test]
+    fn large() {
+        let mut arr: Vec<u32> = (0..1000).map(|i| (i * 7919) % 1009).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        introsort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    // 管风琴形输入会让三数取中反复选出较差的样本
+    #[test]
+    fn organ_pipe() {
+        let mut arr: Vec<u32> = (0..500).chain((0..500).rev()).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        introsort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    #[test]
+    fn heap_fallback() {
+        let mut arr: Vec<u32> = (0..100).rev().collect();
+
+        super::intro(&mut arr, 0, &mut u32::cmp, &mut Probe::new(&mut ()));
+        assert!(arr.is_sorted());
+    }
+
+    #[test]
+    fn reverse() {
+        let mut arr: Vec<u32> = (0..100).collect();
+        introsort_by(&mut arr, |a, b| b.cmp(a));
+        assert!(arr.iter().rev().is_sorted());
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr: Vec<(u32, u32)> = (0..100).map(|i| (i % 7, i)).collect();
+        introsort_by_key(&mut arr, |&(k, _)| k);
+        assert!(arr.is_sorted_by_key(|&(k, _)| k));
+    }
+}
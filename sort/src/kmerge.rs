@@ -0,0 +1,212 @@
+use std::cmp::Ordering;
+use std::iter::FusedIterator;
+
+use crate::heap::sink;
+use crate::observe::Probe;
+
+// 各来源的元素类型
+type Item<I> = <<I as IntoIterator>::Item as IntoIterator>::Item;
+
+type Source<I> = <<I as IntoIterator>::Item as IntoIterator>::IntoIter;
+
+// 惰性地多路归并若干有序序列；相等的元素按来源的先后产出，因而是稳定的
+pub fn kmerge<I>(sources: I) -> KMerge<Source<I>, impl FnMut(&Item<I>, &Item<I>) -> Ordering>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    Item<I>: Ord,
+{
+    kmerge_by(sources, Item::<I>::cmp)
+}
+
+pub fn kmerge_by<I, F>(sources: I, compare: F) -> KMerge<Source<I>, F>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    F: FnMut(&Item<I>, &Item<I>) -> Ordering,
+{
+    KMerge::new(
+        sources.into_iter().map(IntoIterator::into_iter).collect(),
+        compare,
+    )
+}
+
+pub fn kmerge_by_key<I, K, F>(
+    sources: I,
+    mut f: F,
+) -> KMerge<Source<I>, impl FnMut(&Item<I>, &Item<I>) -> Ordering>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    K: Ord,
+    F: FnMut(&Item<I>) -> K,
+{
+    kmerge_by(sources, move |a, b| f(a).cmp(&f(b)))
+}
+
+// 用二叉堆实现：每个来源只在堆中放一个首元素，
+// 取出堆顶后再从同一来源补充，每产出一个元素需O(log k)次比较
+pub struct KMerge<I: Iterator, F> {
+    sources: Vec<I>,
+    // 小顶堆，元素为(来源的首元素, 来源下标)
+    heap: Vec<(I::Item, usize)>,
+    compare: F,
+}
+
+impl<I, F> KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    pub(crate) fn new(mut sources: Vec<I>, mut compare: F) -> Self {
+        let mut heap: Vec<_> = sources
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, source)| Some((source.next()?, i)))
+            .collect();
+
+        for parent in (0..heap.len() / 2).rev() {
+            sink(
+                &mut heap,
+                parent,
+                &mut heap_order(&mut compare),
+                &mut Probe::new(&mut ()),
+            );
+        }
+
+        Self {
+            sources,
+            heap,
+            compare,
+        }
+    }
+}
+
+// sink与swim维护的是大顶堆，这里把次序倒过来：
+// 元素小者优先，相等时来源下标小者优先
+fn heap_order<T, F>(compare: &mut F) -> impl FnMut(&(T, usize), &(T, usize)) -> Ordering
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    |(a, i), (b, j)| compare(b, a).then(j.cmp(i))
+}
+
+impl<I, F> Iterator for KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let last = self.heap.len().checked_sub(1)?;
+        let mut compare = heap_order(&mut self.compare);
+
+        // 从同一来源补充，直接替换堆顶再下沉；来源耗尽则用末尾元素填补
+        let source = self.heap[0].1;
+        let (item, _) = match self.sources[source].next() {
+            Some(next) => std::mem::replace(&mut self.heap[0], (next, source)),
+            None => {
+                self.heap.swap(0, last);
+                self.heap.pop()?
+            }
+        };
+
+        if !self.heap.is_empty() {
+            sink(&mut self.heap, 0, &mut compare, &mut Probe::new(&mut ()));
+        }
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.iter().map(Iterator::size_hint).fold(
+            (self.heap.len(), Some(self.heap.len())),
+            |(lo, hi), (l, h)| {
+                (
+                    lo.saturating_add(l),
+                    hi.zip(h).and_then(|(a, b)| a.checked_add(b)),
+                )
+            },
+        )
+    }
+}
+
+impl<I, F> FusedIterator for KMerge<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::{kmerge, kmerge_by, kmerge_by_key};
+
+    #[test]
+    fn empty() {
+        assert_eq!(kmerge(Vec::<Vec<u32>>::new()).count(), 0);
+        assert_eq!(kmerge([vec![], vec![], Vec::<u32>::new()]).count(), 0);
+    }
+
+    #[test]
+    fn basic() {
+        let merged: Vec<u32> =
+            kmerge([vec![1, 4, 7], vec![], vec![2, 5, 8, 9], vec![3, 6]]).collect();
+        assert_eq!(merged, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn random_sources() {
+        let mut rng = rand::thread_rng();
+        let mut sources: Vec<Vec<u32>> = (0..37)
+            .map(|_| {
+                (0..rng.gen_range(0..200))
+                    .map(|_| rng.gen_range(0..1000))
+                    .collect()
+            })
+            .collect();
+        for source in &mut sources {
+            source.sort();
+        }
+
+        let mut expected = sources.concat();
+        expected.sort();
+
+        let merge = kmerge(sources);
+        assert_eq!(merge.size_hint(), (expected.len(), Some(expected.len())));
+        assert_eq!(merge.collect::<Vec<_>>(), expected);
+    }
+
+    // 相等的元素按来源下标产出
+    #[test]
+    fn stable() {
+        let sources = (0..5).map(|s| (0..100).map(move |i| (i / 10, s)));
+        let merged: Vec<(u32, u32)> = kmerge_by_key(sources, |&(k, _)| k).collect();
+
+        let mut expected: Vec<(u32, u32)> = (0..5)
+            .flat_map(|s| (0..100).map(move |i| (i / 10, s)))
+            .collect();
+        expected.sort();
+
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn reverse() {
+        let merged: Vec<u32> =
+            kmerge_by([vec![9, 5, 1], vec![8, 2], vec![7]], |a, b| b.cmp(a)).collect();
+        assert_eq!(merged, [9, 8, 7, 5, 2, 1]);
+    }
+
+    // 只在需要时才从来源取元素，因而可以归并无穷序列
+    #[test]
+    fn lazy() {
+        let merged: Vec<u64> = kmerge([(0..).step_by(3), (1..).step_by(3), (2..).step_by(3)])
+            .take(10)
+            .collect();
+        assert_eq!(merged, (0..10).collect::<Vec<_>>());
+    }
+}
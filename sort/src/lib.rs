@@ -1,17 +1,54 @@
-#![feature(maybe_uninit_write_slice)]
-
+mod algorithm;
 mod bubble;
+mod external;
 mod heap;
 mod insertion;
+mod intro;
+mod kmerge;
 mod merge;
+mod observe;
+mod parallel;
+mod pdq;
 mod quick;
+mod radix;
+mod select;
 mod selection;
 mod shell;
 
-pub use self::bubble::bubble;
-pub use self::heap::heap;
-pub use self::insertion::insertion;
-pub use self::merge::msort;
-pub use self::quick::qsort;
-pub use self::selection::selection;
-pub use self::shell::shell;
+pub use self::algorithm::{
+    BottomUpMerge, Bubble, Heap, Insertion, Introsort, Merge, NaturalMerge, Pdqsort, Quick,
+    Selection, Shell, Sort, StableSort, Timsort,
+};
+pub use self::bubble::{bubble, bubble_by, bubble_by_key};
+pub use self::external::{Codec, ExternalSort, LengthPrefixed, Lines, Sorted};
+pub use self::heap::{heap, heap_by, heap_by_key};
+pub use self::insertion::{insertion, insertion_by, insertion_by_key};
+pub use self::intro::{introsort, introsort_by, introsort_by_key};
+pub use self::kmerge::{KMerge, kmerge, kmerge_by, kmerge_by_key};
+pub use self::merge::{
+    bottom_up_msort, bottom_up_msort_by, bottom_up_msort_by_key, msort, msort_by, msort_by_key,
+    natural_msort, natural_msort_by, natural_msort_by_key, timsort, timsort_by, timsort_by_key,
+};
+pub use self::observe::{Counter, SortObserver, Step};
+pub use self::parallel::{
+    par_msort, par_msort_by, par_msort_by_key, par_qsort, par_qsort_by, par_qsort_by_key,
+};
+pub use self::pdq::{pdqsort, pdqsort_by, pdqsort_by_key};
+pub use self::quick::{
+    Pivot, qsort, qsort_by, qsort_by_key, qsort_with, qsort_with_by, qsort_with_by_key,
+    qsort_with_observed,
+};
+pub use self::radix::{
+    RadixKey, SortKey, counting_sort, counting_sort_by_key, counting_sort_observed, lsd_radix_sort,
+    lsd_radix_sort_by_key, lsd_radix_sort_observed, msd_radix_sort, msd_radix_sort_by_key,
+    msd_radix_sort_observed,
+};
+pub use self::select::{
+    partial_sort, partial_sort_by, partial_sort_by_key, partial_sort_observed, select_nth,
+    select_nth_by, select_nth_by_key, select_nth_observed, top_k, top_k_by, top_k_by_key,
+};
+pub use self::selection::{selection, selection_by, selection_by_key};
+pub use self::shell::{
+    Ciura, Knuth, Sedgewick, ShellGaps, ShellStats, Tokuda, shell, shell_by, shell_by_key,
+    shell_stats, shell_with, shell_with_by,
+};
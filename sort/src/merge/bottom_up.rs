@@ -0,0 +1,104 @@
+use std::cmp::Ordering;
+
+use super::merge_halves;
+use crate::observe::{Probe, SortObserver};
+
+// 自底向上：先两两合并长度为1的子数组，再合并长度为2的，以此类推
+pub fn bottom_up_msort<T: Ord>(arr: &mut [T]) {
+    bottom_up_msort_by(arr, T::cmp);
+}
+
+pub fn bottom_up_msort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bottom_up_msort_observed(arr, compare, &mut ());
+}
+
+pub(crate) fn bottom_up_msort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+    let len = arr.len();
+
+    // 子数组长度总是小于len的2的幂
+    let mut aux = Box::<[T]>::new_uninit_slice(len.next_power_of_two() / 2);
+    let mut width = 1;
+
+    while width < len {
+        for start in (0..len - width).step_by(2 * width) {
+            let end = (start + 2 * width).min(len);
+            merge_halves(
+                &mut arr[start..end],
+                width,
+                &mut aux,
+                &mut compare,
+                &mut probe.at(start),
+            );
+        }
+
+        width *= 2;
+    }
+}
+
+pub fn bottom_up_msort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    bottom_up_msort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bottom_up_msort, bottom_up_msort_by, bottom_up_msort_by_key};
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+
+        bottom_up_msort(&mut arr);
+    }
+
+    #[test]
+    fn basic() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+
+        bottom_up_msort(&mut arr);
+
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7, 8, 9, 10, 13, 14, 16, 17]);
+    }
+
+    #[test]
+    fn odd_lengths() {
+        for len in 0..70u32 {
+            let mut arr: Vec<u32> = (0..len).map(|i| (i * 37) % 11).collect();
+            let mut expected = arr.clone();
+            expected.sort();
+
+            bottom_up_msort(&mut arr);
+
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+
+        bottom_up_msort_by(&mut arr, |a, b| b.cmp(a));
+
+        assert_eq!(arr, [17, 16, 14, 13, 10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+
+        bottom_up_msort_by_key(&mut arr, |&(k, _)| k);
+
+        assert_eq!(arr, [(1, 'b'), (1, 'd'), (2, 'c'), (3, 'a')]);
+    }
+}
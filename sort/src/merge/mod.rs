@@ -0,0 +1,228 @@
+mod bottom_up;
+mod natural;
+mod tim;
+
+use std::cmp::Ordering;
+use std::mem::MaybeUninit;
+use std::ptr;
+
+use crate::observe::{Probe, SortObserver};
+
+pub(crate) use self::bottom_up::bottom_up_msort_observed;
+pub use self::bottom_up::{bottom_up_msort, bottom_up_msort_by, bottom_up_msort_by_key};
+pub(crate) use self::natural::natural_msort_observed;
+pub use self::natural::{natural_msort, natural_msort_by, natural_msort_by_key};
+pub(crate) use self::tim::timsort_observed;
+pub use self::tim::{timsort, timsort_by, timsort_by_key};
+
+pub fn msort<T: Ord>(arr: &mut [T]) {
+    msort_by(arr, T::cmp);
+}
+
+pub fn msort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    msort_observed(arr, compare, &mut ());
+}
+
+pub fn msort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    msort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub(crate) fn msort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    // 归并时只需暂存左半部分
+    let mut aux = Box::<[T]>::new_uninit_slice(arr.len() / 2);
+
+    merge(arr, &mut aux, &mut compare, &mut Probe::new(observer));
+}
+
+pub(crate) fn merge<T, F, O>(
+    arr: &mut [T],
+    aux: &mut [MaybeUninit<T>],
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+
+    if len < 2 {
+        return;
+    }
+
+    let mid = len / 2;
+
+    let (arr_l, arr_r) = arr.split_at_mut(mid);
+    merge(arr_l, aux, compare, &mut probe.at(0));
+    merge(arr_r, aux, compare, &mut probe.at(mid));
+
+    merge_halves(arr, mid, aux, compare, probe);
+}
+
+// 合并有序的 arr[..mid] 与 arr[mid..]，aux 的长度不得小于 mid
+// 比较时以左侧元素原本的下标上报
+pub(crate) fn merge_halves<T, F, O>(
+    arr: &mut [T],
+    mid: usize,
+    aux: &mut [MaybeUninit<T>],
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    let arr = arr.as_mut_ptr();
+    let buf = aux[..mid].as_mut_ptr().cast::<T>();
+
+    unsafe {
+        // 左半部分搬进辅助区，arr[..mid] 成了空洞
+        ptr::copy_nonoverlapping(arr, buf, mid);
+
+        let mut hole = Hole {
+            src: buf,
+            end: buf.add(mid),
+            dst: arr,
+        };
+        let mut right = arr.add(mid);
+        let arr_end = arr.add(len);
+
+        // 写入位置总落后于右游标，不会覆盖未读的右侧元素
+        while hole.src < hole.end && right < arr_end {
+            // 右侧严格更小才取右侧，保证稳定
+            probe.compare(
+                hole.src.offset_from_unsigned(buf),
+                right.offset_from_unsigned(arr),
+            );
+
+            let src = if compare(&*right, &*hole.src) == Ordering::Less {
+                let src = right;
+                right = right.add(1);
+                src
+            } else {
+                let src = hole.src;
+                hole.src = hole.src.add(1);
+                src
+            };
+
+            ptr::copy_nonoverlapping(src, hole.dst, 1);
+            probe.write(hole.dst.offset_from_unsigned(arr));
+            hole.dst = hole.dst.add(1);
+        }
+
+        let dst = hole.dst.offset_from_unsigned(arr);
+        for i in dst..dst + hole.end.offset_from_unsigned(hole.src) {
+            probe.write(i);
+        }
+    }
+
+    // hole 析构时把左侧余下的元素放回空洞；
+    // 即便 compare 恐慌，arr 也仍是原元素的一个排列
+}
+
+pub(crate) struct Hole<T> {
+    src: *mut T,
+    end: *mut T,
+    dst: *mut T,
+}
+
+impl<T> Drop for Hole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            let rest = self.end.offset_from_unsigned(self.src);
+            ptr::copy_nonoverlapping(self.src, self.dst, rest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{msort, msort_by, msort_by_key};
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+
+        msort(arr.as_mut_slice());
+    }
+
+    #[test]
+    fn single() {
+        let mut arr = [1];
+
+        msort(arr.as_mut_slice());
+    }
+
+    #[test]
+    fn top_to_btm() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+
+        msort(&mut arr);
+
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7, 8, 9, 10, 13, 14, 16, 17]);
+    }
+
+    #[test]
+    fn strings() {
+        let mut arr = ["pear", "apple", "fig", "kiwi", "banana"].map(String::from);
+
+        msort(&mut arr);
+
+        assert_eq!(arr, ["apple", "banana", "fig", "kiwi", "pear"]);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+
+        msort_by(&mut arr, |a, b| b.cmp(a));
+
+        assert_eq!(arr, [17, 16, 14, 13, 10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    }
+
+    #[test]
+    fn float_key() {
+        let mut arr = [2.5, -1.0, 0.5, 3.0];
+
+        msort_by(&mut arr, f64::total_cmp);
+
+        assert_eq!(arr, [-1.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+
+        msort_by_key(&mut arr, |&(k, _)| k);
+
+        assert_eq!(arr, [(1, 'b'), (1, 'd'), (2, 'c'), (3, 'a')]);
+    }
+
+    #[test]
+    fn panic_safety() {
+        let mut arr = ["d", "a", "c", "b", "f", "e"].map(String::from);
+        let mut calls = 0;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            msort_by(&mut arr, |a, b| {
+                calls += 1;
+                assert!(calls < 5);
+                a.cmp(b)
+            });
+        }));
+
+        assert!(result.is_err());
+        arr.sort();
+        assert_eq!(arr, ["a", "b", "c", "d", "e", "f"]);
+    }
+}
@@ -0,0 +1,166 @@
+use std::cmp::Ordering;
+
+use super::merge_halves;
+use crate::observe::{Probe, SortObserver};
+
+// 自然归并：利用输入中已有的升序段与降序段，只合并这些段
+pub fn natural_msort<T: Ord>(arr: &mut [T]) {
+    natural_msort_by(arr, T::cmp);
+}
+
+pub fn natural_msort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    natural_msort_observed(arr, compare, &mut ());
+}
+
+pub(crate) fn natural_msort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+    let len = arr.len();
+
+    if len < 2 {
+        return;
+    }
+
+    // 每个段的起点，末尾附上len作哨兵
+    let mut bounds = vec![0];
+    let mut start = 0;
+
+    while start < len {
+        let end = find_run(&mut arr[start..], &mut compare, &mut probe.at(start)) + start;
+        bounds.push(end);
+        start = end;
+    }
+
+    let mut aux = Box::<[T]>::new_uninit_slice(len);
+
+    // 两两合并相邻的段，直至只剩一段
+    while bounds.len() > 2 {
+        let mut merged = Vec::with_capacity(bounds.len() / 2 + 2);
+        let mut i = 0;
+
+        while i + 2 < bounds.len() {
+            let (lo, mid, hi) = (bounds[i], bounds[i + 1], bounds[i + 2]);
+            merge_halves(
+                &mut arr[lo..hi],
+                mid - lo,
+                &mut aux,
+                &mut compare,
+                &mut probe.at(lo),
+            );
+            merged.push(lo);
+            i += 2;
+        }
+
+        // 落单的最后一段原样保留
+        merged.extend_from_slice(&bounds[i..]);
+        bounds = merged;
+    }
+}
+
+pub fn natural_msort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    natural_msort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+// 返回开头那一段的长度，降序段会被翻转成升序
+// 只有严格降序才翻转，相等元素的次序不受影响
+pub(super) fn find_run<T, F, O>(part: &mut [T], compare: &mut F, probe: &mut Probe<O>) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = part.len();
+
+    if len < 2 {
+        return len;
+    }
+
+    let mut end = 2;
+
+    if probe.cmp(part, 1, 0, compare) == Ordering::Less {
+        while end < len && probe.cmp(part, end, end - 1, compare) == Ordering::Less {
+            end += 1;
+        }
+
+        for i in 0..end / 2 {
+            probe.swap(part, i, end - 1 - i);
+        }
+    } else {
+        while end < len && probe.cmp(part, end, end - 1, compare) != Ordering::Less {
+            end += 1;
+        }
+    }
+
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{natural_msort, natural_msort_by, natural_msort_by_key};
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+
+        natural_msort(&mut arr);
+    }
+
+    #[test]
+    fn basic() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+
+        natural_msort(&mut arr);
+
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7, 8, 9, 10, 13, 14, 16, 17]);
+    }
+
+    #[test]
+    fn runs() {
+        // 升序段、降序段、升序段
+        let mut arr = [1, 4, 6, 9, 8, 5, 3, 2, 0, 7, 10, 11];
+
+        natural_msort(&mut arr);
+
+        assert_eq!(arr, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn sawtooth() {
+        for len in 0..70u32 {
+            let mut arr: Vec<u32> = (0..len).map(|i| i % 5).collect();
+            let mut expected = arr.clone();
+            expected.sort();
+
+            natural_msort(&mut arr);
+
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn reverse() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+
+        natural_msort_by(&mut arr, |a, b| b.cmp(a));
+
+        assert_eq!(arr, [17, 16, 14, 13, 10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    }
+
+    #[test]
+    fn descending_run_stable() {
+        let mut arr = [(3, 'a'), (2, 'b'), (2, 'c'), (1, 'd'), (1, 'e')];
+
+        natural_msort_by_key(&mut arr, |&(k, _)| k);
+
+        assert_eq!(arr, [(1, 'd'), (1, 'e'), (2, 'b'), (2, 'c'), (3, 'a')]);
+    }
+}
@@ -0,0 +1,708 @@
+use std::cmp::Ordering;
+use std::mem::MaybeUninit;
+use std::{ptr, slice};
+
+use super::Hole;
+use super::natural::find_run;
+use crate::observe::{Probe, SortObserver};
+
+// 短于此长度的数组直接二分插入排序
+const MIN_MERGE: usize = 32;
+
+// 连续赢这么多次就进入飞奔模式
+const MIN_GALLOP: usize = 7;
+
+// Timsort：找出天然的有序段，短段用二分插入补齐到minrun，
+// 再按栈上的长度不变式合并，合并时一侧连胜便改为飞奔
+pub fn timsort<T: Ord>(arr: &mut [T]) {
+    timsort_by(arr, T::cmp);
+}
+
+pub fn timsort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    timsort_observed(arr, compare, &mut ());
+}
+
+pub fn timsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    timsort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub(crate) fn timsort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = arr.len();
+    let mut probe = Probe::new(observer);
+
+    if len < 2 {
+        return;
+    }
+
+    if len < MIN_MERGE {
+        let run = find_run(arr, &mut compare, &mut probe);
+        binary_insertion(arr, run, &mut compare, &mut probe);
+        return;
+    }
+
+    let mut tim = Tim {
+        runs: Vec::new(),
+        // 合并时只暂存较短的一段
+        tmp: Box::<[T]>::new_uninit_slice(len / 2),
+        min_gallop: MIN_GALLOP,
+    };
+    let min_run = min_run(len);
+    let mut start = 0;
+
+    while start < len {
+        let part = &mut arr[start..];
+        let mut run = find_run(part, &mut compare, &mut probe.at(start));
+
+        // 太短的段补齐到minrun
+        if run < min_run {
+            let forced = min_run.min(part.len());
+            binary_insertion(&mut part[..forced], run, &mut compare, &mut probe.at(start));
+            run = forced;
+        }
+
+        tim.runs.push(Run { start, len: run });
+        tim.merge_collapse(arr, &mut compare, &mut probe);
+        start += run;
+    }
+
+    tim.merge_force_collapse(arr, &mut compare, &mut probe);
+}
+
+// 取n的最高6位，若余下的位中有1则再加1，结果落在[16, 32]
+fn min_run(mut n: usize) -> usize {
+    let mut r = 0;
+
+    while n >= MIN_MERGE {
+        r |= n & 1;
+        n >>= 1;
+    }
+
+    n + r
+}
+
+// part[..sorted]已有序，把其余元素逐个二分插入
+// 插入位置取在相等元素之后，保证稳定
+fn binary_insertion<T, F, O>(part: &mut [T], sorted: usize, compare: &mut F, probe: &mut Probe<O>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    for i in sorted.max(1)..part.len() {
+        let (mut lo, mut hi) = (0, i);
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+
+            if probe.cmp(part, i, mid, compare) == Ordering::Less {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+
+        part[lo..=i].rotate_right(1);
+        for j in lo..=i {
+            probe.write(j);
+        }
+    }
+}
+
+// pred在[0, len)上先真后假，返回首个为假的下标
+// 先以1, 2, 4, ...的步长飞奔确定范围，再二分
+fn gallop(len: usize, from_right: bool, mut pred: impl FnMut(usize) -> bool) -> usize {
+    let mut lo = 0;
+    let mut hi = len;
+    let mut step = 1;
+
+    if from_right {
+        // 探测len - 1, len - 2, len - 4, ...
+        while step <= len {
+            let i = len - step;
+            if pred(i) {
+                lo = i + 1;
+                break;
+            }
+            hi = i;
+            step *= 2;
+        }
+    } else {
+        // 探测0, 1, 3, 7, ...
+        while step - 1 < len {
+            let i = step - 1;
+            if !pred(i) {
+                hi = i;
+                break;
+            }
+            lo = i + 1;
+            step *= 2;
+        }
+    }
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+
+        if pred(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    lo
+}
+
+// 把count个元素从src搬到dst并逐个上报写入，两区间可以重叠
+unsafe fn shift<T, O>(src: *const T, dst: *mut T, count: usize, base: *mut T, probe: &mut Probe<O>)
+where
+    O: SortObserver + ?Sized,
+{
+    unsafe {
+        ptr::copy(src, dst, count);
+
+        let start = dst.offset_from_unsigned(base);
+        for i in start..start + count {
+            probe.write(i);
+        }
+    }
+}
+
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+struct Tim<T> {
+    runs: Vec<Run>,
+    tmp: Box<[MaybeUninit<T>]>,
+    min_gallop: usize,
+}
+
+impl<T> Tim<T> {
+    // 维持栈上的不变式：
+    // runs[i - 2].len > runs[i - 1].len + runs[i].len 且 runs[i - 1].len > runs[i].len
+    fn merge_collapse<F, O>(&mut self, arr: &mut [T], compare: &mut F, probe: &mut Probe<O>)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+            let len = |i: usize| self.runs[i].len;
+
+            if (n > 0 && len(n - 1) <= len(n) + len(n + 1))
+                || (n > 1 && len(n - 2) <= len(n - 1) + len(n))
+            {
+                if len(n - 1) < len(n + 1) {
+                    n -= 1;
+                }
+            } else if len(n) > len(n + 1) {
+                break;
+            }
+
+            self.merge_at(n, arr, compare, probe);
+        }
+    }
+
+    fn merge_force_collapse<F, O>(&mut self, arr: &mut [T], compare: &mut F, probe: &mut Probe<O>)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        while self.runs.len() > 1 {
+            let mut n = self.runs.len() - 2;
+
+            if n > 0 && self.runs[n - 1].len < self.runs[n + 1].len {
+                n -= 1;
+            }
+
+            self.merge_at(n, arr, compare, probe);
+        }
+    }
+
+    // 合并栈上第i段与第i + 1段
+    fn merge_at<F, O>(&mut self, i: usize, arr: &mut [T], compare: &mut F, probe: &mut Probe<O>)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        let Run { start, len: len1 } = self.runs[i];
+        let len2 = self.runs[i + 1].len;
+        let mid = start + len1;
+
+        self.runs[i].len += len2;
+        self.runs.remove(i + 1);
+
+        // 左段中不大于右段首元素的部分已就位
+        let skip = gallop(len1, false, |j| {
+            probe.compare(start + j, mid);
+            compare(&arr[start + j], &arr[mid]) != Ordering::Greater
+        });
+
+        if skip == len1 {
+            return;
+        }
+
+        // 右段中不小于左段末元素的部分也已就位
+        let last = mid - 1;
+        let len2 = gallop(len2, true, |j| {
+            probe.compare(mid + j, last);
+            compare(&arr[mid + j], &arr[last]) == Ordering::Less
+        });
+
+        if len2 == 0 {
+            return;
+        }
+
+        let lo = start + skip;
+        let region = &mut arr[lo..mid + len2];
+        let len1 = len1 - skip;
+        let probe = &mut probe.at(lo);
+
+        unsafe {
+            if len1 <= len2 {
+                merge_lo(
+                    region,
+                    len1,
+                    &mut self.tmp,
+                    &mut self.min_gallop,
+                    compare,
+                    probe,
+                );
+            } else {
+                merge_hi(
+                    region,
+                    len1,
+                    &mut self.tmp,
+                    &mut self.min_gallop,
+                    compare,
+                    probe,
+                );
+            }
+        }
+    }
+}
+
+// 左段较短：暂存左段，自左向右填充
+// 前提：右段首元素小于左段首元素，左段末元素大于右段所有元素
+unsafe fn merge_lo<T, F, O>(
+    region: &mut [T],
+    len1: usize,
+    tmp: &mut [MaybeUninit<T>],
+    min_gallop: &mut usize,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let total = region.len();
+    let arr = region.as_mut_ptr();
+    let buf = tmp[..len1].as_mut_ptr().cast::<T>();
+
+    unsafe {
+        ptr::copy_nonoverlapping(arr, buf, len1);
+
+        // 空洞[dst, dst + 左段余量)恰好紧挨着右游标
+        let mut hole = Hole {
+            src: buf,
+            end: buf.add(len1),
+            dst: arr,
+        };
+        let mut right = len1;
+        let rest = |hole: &Hole<T>| hole.end.offset_from_unsigned(hole.src);
+
+        shift(arr.add(right), hole.dst, 1, arr, probe);
+        hole.dst = hole.dst.add(1);
+        right += 1;
+
+        'outer: while right < total && rest(&hole) > 1 {
+            let (mut count1, mut count2) = (0, 0);
+
+            // 逐个比较，直到某一侧连胜min_gallop次
+            while count1.max(count2) < *min_gallop {
+                probe.compare(hole.src.offset_from_unsigned(buf), right);
+
+                if compare(&*arr.add(right), &*hole.src) == Ordering::Less {
+                    shift(arr.add(right), hole.dst, 1, arr, probe);
+                    hole.dst = hole.dst.add(1);
+                    right += 1;
+                    count1 = 0;
+                    count2 += 1;
+
+                    if right == total {
+                        break 'outer;
+                    }
+                } else {
+                    shift(hole.src, hole.dst, 1, arr, probe);
+                    hole.dst = hole.dst.add(1);
+                    hole.src = hole.src.add(1);
+                    count1 += 1;
+                    count2 = 0;
+
+                    if rest(&hole) == 1 {
+                        break 'outer;
+                    }
+                }
+            }
+
+            // 飞奔：成批搬运
+            loop {
+                let run = slice::from_raw_parts(hole.src, rest(&hole));
+                let key = &*arr.add(right);
+                let base = hole.src.offset_from_unsigned(buf);
+                count1 = gallop(run.len(), false, |j| {
+                    probe.compare(base + j, right);
+                    compare(&run[j], key) != Ordering::Greater
+                });
+
+                shift(hole.src, hole.dst, count1, arr, probe);
+                hole.dst = hole.dst.add(count1);
+                hole.src = hole.src.add(count1);
+
+                if rest(&hole) <= 1 {
+                    break 'outer;
+                }
+
+                shift(arr.add(right), hole.dst, 1, arr, probe);
+                hole.dst = hole.dst.add(1);
+                right += 1;
+
+                if right == total {
+                    break 'outer;
+                }
+
+                let run = slice::from_raw_parts(arr.add(right), total - right);
+                let key = &*hole.src;
+                let pos = hole.src.offset_from_unsigned(buf);
+                count2 = gallop(run.len(), false, |j| {
+                    probe.compare(pos, right + j);
+                    compare(&run[j], key) == Ordering::Less
+                });
+
+                shift(arr.add(right), hole.dst, count2, arr, probe);
+                hole.dst = hole.dst.add(count2);
+                right += count2;
+
+                if right == total {
+                    break 'outer;
+                }
+
+                shift(hole.src, hole.dst, 1, arr, probe);
+                hole.dst = hole.dst.add(1);
+                hole.src = hole.src.add(1);
+
+                if rest(&hole) == 1 {
+                    break 'outer;
+                }
+
+                *min_gallop = min_gallop.saturating_sub(1);
+
+                if count1 < MIN_GALLOP && count2 < MIN_GALLOP {
+                    break;
+                }
+            }
+
+            // 退出飞奔的代价更高了
+            *min_gallop += 2;
+        }
+
+        *min_gallop = (*min_gallop).max(1);
+
+        // 左段只剩最后一个（它最大），右段余下的先左移
+        if rest(&hole) == 1 {
+            let count = total - right;
+            shift(arr.add(right), hole.dst, count, arr, probe);
+            hole.dst = hole.dst.add(count);
+        }
+
+        let dst = hole.dst.offset_from_unsigned(arr);
+        for i in dst..dst + rest(&hole) {
+            probe.write(i);
+        }
+    }
+
+    // hole析构时把暂存的余下元素放回空洞
+}
+
+// 右段较短：暂存右段，自右向左填充
+// 前提：左段末元素大于右段所有元素，右段首元素小于左段首元素
+unsafe fn merge_hi<T, F, O>(
+    region: &mut [T],
+    len1: usize,
+    tmp: &mut [MaybeUninit<T>],
+    min_gallop: &mut usize,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len2 = region.len() - len1;
+    let arr = region.as_mut_ptr();
+    let buf = tmp[..len2].as_mut_ptr().cast::<T>();
+
+    unsafe {
+        ptr::copy_nonoverlapping(arr.add(len1), buf, len2);
+
+        // 左段余下[0, left)，空洞为[left, left + 右段余量)
+        let mut hole = Hole {
+            src: buf,
+            end: buf.add(len2),
+            dst: arr.add(len1),
+        };
+        let mut left = len1;
+        let rest = |hole: &Hole<T>| hole.end.offset_from_unsigned(hole.src);
+
+        // 空洞的最后一格
+        let last = |hole: &Hole<T>| hole.dst.add(rest(hole) - 1);
+
+        macro_rules! take_left {
+            () => {
+                left -= 1;
+                shift(arr.add(left), last(&hole), 1, arr, probe);
+                hole.dst = arr.add(left);
+            };
+        }
+
+        macro_rules! take_right {
+            () => {
+                hole.end = hole.end.sub(1);
+                let dst = hole.dst.add(rest(&hole));
+                shift(hole.end, dst, 1, arr, probe);
+            };
+        }
+
+        take_left!();
+
+        'outer: while left > 0 && rest(&hole) > 1 {
+            let (mut count1, mut count2) = (0, 0);
+
+            while count1.max(count2) < *min_gallop {
+                let b = rest(&hole) - 1;
+                probe.compare(left - 1, len1 + b);
+
+                if compare(&*buf.add(b), &*arr.add(left - 1)) == Ordering::Less {
+                    take_left!();
+                    count1 += 1;
+                    count2 = 0;
+
+                    if left == 0 {
+                        break 'outer;
+                    }
+                } else {
+                    take_right!();
+                    count1 = 0;
+                    count2 += 1;
+
+                    if rest(&hole) == 1 {
+                        break 'outer;
+                    }
+                }
+            }
+
+            loop {
+                // 左段中大于右段末元素的部分整体右移
+                let b = rest(&hole) - 1;
+                let run = slice::from_raw_parts(arr, left);
+                let key = &*buf.add(b);
+                let k = gallop(left, true, |j| {
+                    probe.compare(j, len1 + b);
+                    compare(&run[j], key) != Ordering::Greater
+                });
+                count1 = left - k;
+
+                shift(arr.add(k), arr.add(k + rest(&hole)), count1, arr, probe);
+                left = k;
+                hole.dst = arr.add(left);
+
+                if left == 0 {
+                    break 'outer;
+                }
+
+                take_right!();
+
+                if rest(&hole) == 1 {
+                    break 'outer;
+                }
+
+                // 右段中不小于左段末元素的部分整体写回
+                let run = slice::from_raw_parts(buf, rest(&hole));
+                let key = &*arr.add(left - 1);
+                let k = gallop(run.len(), true, |j| {
+                    probe.compare(len1 + j, left - 1);
+                    compare(&run[j], key) == Ordering::Less
+                });
+                count2 = run.len() - k;
+
+                shift(buf.add(k), hole.dst.add(k), count2, arr, probe);
+                hole.end = buf.add(k);
+
+                if rest(&hole) <= 1 {
+                    break 'outer;
+                }
+
+                take_left!();
+
+                if left == 0 {
+                    break 'outer;
+                }
+
+                *min_gallop = min_gallop.saturating_sub(1);
+
+                if count1 < MIN_GALLOP && count2 < MIN_GALLOP {
+                    break;
+                }
+            }
+
+            *min_gallop += 2;
+        }
+
+        *min_gallop = (*min_gallop).max(1);
+
+        // 右段只剩最前一个（它最小），左段余下的先右移
+        if rest(&hole) == 1 {
+            shift(arr, arr.add(1), left, arr, probe);
+            hole.dst = arr;
+        }
+
+        let dst = hole.dst.offset_from_unsigned(arr);
+        for i in dst..dst + rest(&hole) {
+            probe.write(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::{min_run, timsort, timsort_by, timsort_by_key};
+    use crate::msort;
+
+    fn check(mut arr: Vec<u32>) {
+        let mut expected = arr.clone();
+        msort(&mut expected);
+
+        timsort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    fn random(len: usize, range: u32) -> Vec<u32> {
+        let mut rng = rand::thread_rng();
+        (0..len).map(|_| rng.gen_range(0..range)).collect()
+    }
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+        timsort(&mut arr);
+    }
+
+    #[test]
+    fn minrun() {
+        assert_eq!(min_run(31), 31);
+        assert_eq!(min_run(64), 16);
+        assert_eq!(min_run(65), 17);
+        assert_eq!(min_run(2048), 16);
+        assert_eq!(min_run(2112), 17);
+    }
+
+    #[test]
+    fn random_input() {
+        for len in [5, 31, 32, 100, 1000, 10000] {
+            check(random(len, 1 << 20));
+            check(random(len, 8));
+        }
+    }
+
+    #[test]
+    fn sorted_input() {
+        check((0..5000).collect());
+    }
+
+    #[test]
+    fn reversed_input() {
+        check((0..5000).rev().collect());
+    }
+
+    #[test]
+    fn sawtooth() {
+        for teeth in [3, 50, 700] {
+            check((0..5000).map(|i| i % teeth).collect());
+            check((0..5000).map(|i| teeth - i % teeth).collect());
+        }
+    }
+
+    // 交错的长有序块会触发飞奔模式
+    #[test]
+    fn gallop() {
+        let mut arr: Vec<u32> = (0..2000).step_by(2).collect();
+        arr.extend((1..2000).step_by(2));
+        check(arr);
+
+        let mut arr: Vec<u32> = (1000..2000).collect();
+        arr.extend(0..1000);
+        arr.extend(500..1500);
+        check(arr);
+    }
+
+    #[test]
+    fn stable() {
+        let mut arr: Vec<(u32, usize)> = random(5000, 16).into_iter().zip(0..).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        timsort_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr, expected);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut arr = random(1000, 100);
+        timsort_by(&mut arr, |a, b| b.cmp(a));
+        assert!(arr.iter().rev().is_sorted());
+    }
+
+    #[test]
+    fn strings() {
+        let mut arr: Vec<String> = random(3000, 500).iter().map(u32::to_string).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        timsort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    #[test]
+    fn panic_safety() {
+        let mut arr: Vec<String> = random(1000, 100).iter().map(u32::to_string).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        for limit in [10, 500, 3000, 6000] {
+            let mut calls = 0;
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                timsort_by(&mut arr, |a, b| {
+                    calls += 1;
+                    assert!(calls < limit);
+                    a.cmp(b)
+                });
+            }));
+
+            let mut after = arr.clone();
+            after.sort();
+            assert_eq!(after, expected);
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::cmp::Ordering;
+
+// 排序过程的观察者，下标均相对于整个被排序的数组
+// 并行版本不支持观察；top_k、kmerge与外部排序不在数组上原地进行，同样不支持
+pub trait SortObserver {
+    // 比较了下标i与j处的元素
+    fn compare(&mut self, i: usize, j: usize) {
+        let _ = (i, j);
+    }
+
+    // 交换了下标i与j处的元素
+    fn swap(&mut self, i: usize, j: usize) {
+        let _ = (i, j);
+    }
+
+    // 从辅助区写回了下标i处的元素
+    fn write(&mut self, i: usize) {
+        let _ = i;
+    }
+}
+
+// 什么也不做，未被观察的排序都用它
+impl SortObserver for () {}
+
+// 统计各类操作的次数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Counter {
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub writes: usize,
+}
+
+impl SortObserver for Counter {
+    fn compare(&mut self, _: usize, _: usize) {
+        self.comparisons += 1;
+    }
+
+    fn swap(&mut self, _: usize, _: usize) {
+        self.swaps += 1;
+    }
+
+    fn write(&mut self, _: usize) {
+        self.writes += 1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Write(usize),
+}
+
+// 记录完整的步骤，可用于可视化
+impl SortObserver for Vec<Step> {
+    fn compare(&mut self, i: usize, j: usize) {
+        self.push(Step::Compare(i, j));
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.push(Step::Swap(i, j));
+    }
+
+    fn write(&mut self, i: usize) {
+        self.push(Step::Write(i));
+    }
+}
+
+// 算法内部对观察者的包装：记住子区间的起点，把局部下标换算成全局下标
+pub(crate) struct Probe<'a, O: ?Sized> {
+    observer: &'a mut O,
+    base: usize,
+}
+
+impl<'a, O> Probe<'a, O>
+where
+    O: SortObserver + ?Sized,
+{
+    pub(crate) fn new(observer: &'a mut O) -> Self {
+        Self { observer, base: 0 }
+    }
+
+    // 起点为start的子区间
+    pub(crate) fn at(&mut self, start: usize) -> Probe<'_, O> {
+        Probe {
+            observer: self.observer,
+            base: self.base + start,
+        }
+    }
+
+    // 把起点后移，用于循环中替换当前区间
+    pub(crate) fn advance(&mut self, start: usize) {
+        self.base += start;
+    }
+
+    pub(crate) fn cmp<T, F>(&mut self, arr: &[T], i: usize, j: usize, compare: &mut F) -> Ordering
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.compare(i, j);
+        compare(&arr[i], &arr[j])
+    }
+
+    pub(crate) fn swap<T>(&mut self, arr: &mut [T], i: usize, j: usize) {
+        SortObserver::swap(self, i, j);
+        arr.swap(i, j);
+    }
+}
+
+// 探针本身也是观察者，以便把子区间交给其他算法
+impl<O> SortObserver for Probe<'_, O>
+where
+    O: SortObserver + ?Sized,
+{
+    fn compare(&mut self, i: usize, j: usize) {
+        self.observer.compare(self.base + i, self.base + j);
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.observer.swap(self.base + i, self.base + j);
+    }
+
+    fn write(&mut self, i: usize) {
+        self.observer.write(self.base + i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Counter, Step};
+    use crate::{
+        BottomUpMerge, Bubble, Heap, Insertion, Introsort, Merge, NaturalMerge, Pdqsort, Pivot,
+        Quick, Selection, Shell, Sort, Timsort, counting_sort_observed, lsd_radix_sort_observed,
+        msd_radix_sort_observed, partial_sort_observed, qsort_with_observed, select_nth_observed,
+    };
+
+    fn count<S: Sort<u32>>(arr: &[u32]) -> Counter {
+        let mut arr = arr.to_vec();
+        let mut counter = Counter::default();
+
+        S::sort_observed(&mut arr, u32::cmp, &mut counter);
+        assert!(arr.is_sorted());
+
+        counter
+    }
+
+    // 用记录下的步骤重放排序，结果应当一致
+    fn replay<S: Sort<u32>>(arr: &[u32]) {
+        let mut sorted = arr.to_vec();
+        let mut trace = Vec::new();
+        S::sort_observed(&mut sorted, u32::cmp, &mut trace);
+
+        let mut replayed = arr.to_vec();
+        for step in trace {
+            if let Step::Swap(i, j) = step {
+                replayed.swap(i, j);
+            }
+        }
+
+        assert_eq!(replayed, sorted);
+    }
+
+    #[test]
+    fn sorted_input() {
+        let arr: Vec<u32> = (0..100).collect();
+
+        assert_eq!(
+            count::<Bubble>(&arr),
+            Counter {
+                comparisons: 99,
+                swaps: 0,
+                writes: 0
+            }
+        );
+        assert_eq!(count::<Insertion>(&arr).swaps, 0);
+        assert_eq!(count::<Selection>(&arr).comparisons, 100 * 99 / 2);
+    }
+
+    #[test]
+    fn reversed_input() {
+        let arr: Vec<u32> = (0..100).rev().collect();
+
+        assert_eq!(count::<Bubble>(&arr).swaps, 100 * 99 / 2);
+        assert_eq!(count::<Insertion>(&arr).swaps, 100 * 99 / 2);
+        assert_eq!(count::<Selection>(&arr).swaps, 100);
+    }
+
+    #[test]
+    fn merge_writes() {
+        let arr: Vec<u32> = (0..64).rev().collect();
+
+        // 每层归并都要写回该层所有元素
+        assert_eq!(count::<Merge>(&arr).writes, 64 * 6);
+        assert_eq!(count::<BottomUpMerge>(&arr).writes, 64 * 6);
+        // 整体是一个降序段，翻转即可
+        assert_eq!(
+            count::<NaturalMerge>(&arr),
+            Counter {
+                comparisons: 63,
+                swaps: 32,
+                writes: 0
+            }
+        );
+    }
+
+    #[test]
+    fn every_algorithm() {
+        let arr: Vec<u32> = (0..200).map(|i| (i * 7919) % 101).collect();
+
+        for counter in [
+            count::<BottomUpMerge>(&arr),
+            count::<Bubble>(&arr),
+            count::<Heap>(&arr),
+            count::<Insertion>(&arr),
+            count::<Introsort>(&arr),
+            count::<Merge>(&arr),
+            count::<NaturalMerge>(&arr),
+            count::<Pdqsort>(&arr),
+            count::<Quick>(&arr),
+            count::<Selection>(&arr),
+            count::<Shell>(&arr),
+            count::<Timsort>(&arr),
+        ] {
+            assert!(counter.comparisons > 0);
+        }
+    }
+
+    #[test]
+    fn trace() {
+        let arr: Vec<u32> = (0..200).map(|i| (i * 7919) % 101).collect();
+
+        replay::<Bubble>(&arr);
+        replay::<Heap>(&arr);
+        replay::<Insertion>(&arr);
+        replay::<Introsort>(&arr);
+        replay::<Pdqsort>(&arr);
+        replay::<Quick>(&arr);
+        replay::<Selection>(&arr);
+        replay::<Shell>(&arr);
+    }
+
+    #[test]
+    fn selection_trace() {
+        let arr: Vec<u32> = (0..200).map(|i| (i * 7919) % 101).collect();
+
+        let replay = |trace: Vec<Step>| {
+            let mut replayed = arr.clone();
+            for step in trace {
+                if let Step::Swap(i, j) = step {
+                    replayed.swap(i, j);
+                }
+            }
+            replayed
+        };
+
+        let mut selected = arr.clone();
+        let mut trace = Vec::new();
+        select_nth_observed(&mut selected, 150, u32::cmp, &mut trace);
+        assert!(!trace.is_empty());
+        assert_eq!(replay(trace), selected);
+
+        let mut partial = arr.clone();
+        let mut trace = Vec::new();
+        partial_sort_observed(&mut partial, 20, u32::cmp, &mut trace);
+        assert!(partial[..20].is_sorted());
+        assert_eq!(replay(trace), partial);
+
+        for pivot in [
+            Pivot::First,
+            Pivot::MedianOfThree,
+            Pivot::Ninther,
+            Pivot::Random,
+        ] {
+            let mut sorted = arr.clone();
+            let mut trace = Vec::new();
+            qsort_with_observed(
+                &mut sorted,
+                pivot,
+                &mut rand::thread_rng(),
+                u32::cmp,
+                &mut trace,
+            );
+            assert!(sorted.is_sorted());
+            assert_eq!(replay(trace), sorted);
+        }
+    }
+
+    #[test]
+    fn radix_trace() {
+        let arr: Vec<u32> = (0..200).map(|i| (i * 7919) % 1013).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        let replay = |trace: Vec<Step>| {
+            let mut replayed = arr.clone();
+            for step in trace {
+                match step {
+                    Step::Swap(i, j) => replayed.swap(i, j),
+                    _ => panic!("radix sorts only swap"),
+                }
+            }
+            replayed
+        };
+
+        let mut trace = Vec::new();
+        counting_sort_observed(&mut arr.clone(), |&x| x, &mut trace);
+        assert_eq!(replay(trace), expected);
+
+        let mut trace = Vec::new();
+        lsd_radix_sort_observed(&mut arr.clone(), |&x| x, &mut trace);
+        assert_eq!(replay(trace), expected);
+
+        let strings: Vec<String> = arr.iter().map(|x| format!("{x:04}")).collect();
+        let mut sorted = strings.clone();
+        let mut trace = Vec::new();
+        msd_radix_sort_observed(&mut sorted, |s| s.as_bytes(), &mut trace);
+
+        let mut replayed = strings;
+        for step in trace {
+            if let Step::Swap(i, j) = step {
+                replayed.swap(i, j);
+            }
+        }
+        assert_eq!(replayed, sorted);
+        assert!(sorted.is_sorted());
+    }
+}
@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+use std::mem::MaybeUninit;
+use std::thread;
+
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+use crate::merge::{merge, merge_halves};
+use crate::observe::Probe;
+use crate::quick::{partition, quick};
+
+// 长度不超过 threshold 的区间不再分派线程，交给顺序版本
+// 分派的层数也不超过 log2(可用的并行度)，线程总数与输入长度无关
+pub fn par_msort<T>(arr: &mut [T], threshold: usize)
+where
+    T: Ord + Send,
+{
+    par_msort_by(arr, threshold, T::cmp);
+}
+
+pub fn par_msort_by<T, F>(arr: &mut [T], threshold: usize, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    // 整个排序共用一块辅助区，随递归一分为二
+    let mut aux = Box::<[T]>::new_uninit_slice(arr.len() / 2);
+
+    par_merge(arr, &mut aux, threshold, spawn_depth(), &compare);
+}
+
+pub fn par_msort_by_key<T, K, F>(arr: &mut [T], threshold: usize, f: F)
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    par_msort_by(arr, threshold, |a, b| f(a).cmp(&f(b)));
+}
+
+fn par_merge<T, F>(
+    arr: &mut [T],
+    aux: &mut [MaybeUninit<T>],
+    threshold: usize,
+    depth: u32,
+    mut compare: &F,
+) where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let len = arr.len();
+
+    if len < 2 || len <= threshold || depth == 0 {
+        merge(arr, aux, &mut compare, &mut Probe::new(&mut ()));
+        return;
+    }
+
+    let mid = len / 2;
+    let (arr_l, arr_r) = arr.split_at_mut(mid);
+    let (aux_l, aux_r) = aux.split_at_mut(mid / 2);
+
+    // 左半部分交给新线程，右半部分留在当前线程
+    thread::scope(|s| {
+        s.spawn(|| par_merge(arr_l, aux_l, threshold, depth - 1, compare));
+        par_merge(arr_r, aux_r, threshold, depth - 1, compare);
+    });
+
+    merge_halves(arr, mid, aux, &mut compare, &mut Probe::new(&mut ()));
+}
+
+pub fn par_qsort<T>(arr: &mut [T], threshold: usize)
+where
+    T: Ord + Send,
+{
+    par_qsort_by(arr, threshold, T::cmp);
+}
+
+pub fn par_qsort_by<T, F>(arr: &mut [T], threshold: usize, compare: F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    // 打乱数组，防止最坏情况出现
+    let mut rng = thread_rng();
+    arr.shuffle(&mut rng);
+
+    par_quick(arr, threshold, spawn_depth(), &compare);
+}
+
+pub fn par_qsort_by_key<T, K, F>(arr: &mut [T], threshold: usize, f: F)
+where
+    T: Send,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    par_qsort_by(arr, threshold, |a, b| f(a).cmp(&f(b)));
+}
+
+fn par_quick<T, F>(part: &mut [T], threshold: usize, depth: u32, mut compare: &F)
+where
+    T: Send,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    if part.len() <= threshold.max(1) || depth == 0 {
+        quick(part, &mut compare, &mut Probe::new(&mut ()));
+        return;
+    }
+
+    let (lt, gt) = partition(part, &mut compare, &mut Probe::new(&mut ()));
+    let (left, rest) = part.split_at_mut(lt);
+    let right = &mut rest[(gt - lt)..];
+
+    // 两侧互不相交，可以并行
+    thread::scope(|s| {
+        s.spawn(|| par_quick(left, threshold, depth - 1, compare));
+        par_quick(right, threshold, depth - 1, compare);
+    });
+}
+
+// 每层把线程数翻倍，分派到可用的并行度为止
+fn spawn_depth() -> u32 {
+    thread::available_parallelism().map_or(0, |n| n.get().ilog2())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::qsort;
+
+    fn random(len: usize) -> Vec<u32> {
+        let mut rng = thread_rng();
+        (0..len).map(|_| rng.gen_range(0..1000)).collect()
+    }
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+        par_msort(&mut arr, 0);
+        par_qsort(&mut arr, 0);
+    }
+
+    #[test]
+    fn msort_matches_sequential() {
+        for threshold in [0, 1, 16, 1000] {
+            let mut arr = random(5000);
+            let mut expected = arr.clone();
+            crate::msort(&mut expected);
+
+            par_msort(&mut arr, threshold);
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn qsort_matches_sequential() {
+        for threshold in [0, 1, 16, 1000] {
+            let mut arr = random(5000);
+            let mut expected = arr.clone();
+            qsort(&mut expected);
+
+            par_qsort(&mut arr, threshold);
+            assert_eq!(arr, expected);
+        }
+    }
+
+    // threshold为0时线程数也受并行度限制
+    #[test]
+    fn large_without_threshold() {
+        let mut arr = random(200_000);
+        let mut expected = arr.clone();
+        expected.sort();
+
+        par_msort(&mut arr, 0);
+        assert_eq!(arr, expected);
+
+        arr.reverse();
+        par_qsort(&mut arr, 0);
+        assert_eq!(arr, expected);
+    }
+
+    #[test]
+    fn msort_stable() {
+        let arr = random(5000);
+        let mut tagged: Vec<(u32, usize)> = arr.into_iter().zip(0..).collect();
+        let mut expected = tagged.clone();
+        crate::msort_by_key(&mut expected, |&(k, _)| k);
+
+        par_msort_by_key(&mut tagged, 64, |&(k, _)| k);
+        assert_eq!(tagged, expected);
+    }
+
+    #[test]
+    fn qsort_by_key() {
+        let mut arr: Vec<(u32, usize)> = random(5000).into_iter().zip(0..).collect();
+
+        par_qsort_by_key(&mut arr, 64, |&(k, _)| k);
+        assert!(arr.is_sorted_by_key(|&(k, _)| k));
+    }
+
+    #[test]
+    fn strings() {
+        let mut arr: Vec<String> = random(2000).iter().map(u32::to_string).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        par_msort_by(&mut arr, 100, |a, b| a.cmp(b));
+        assert_eq!(arr, expected);
+
+        arr.reverse();
+        par_qsort_by(&mut arr, 100, |a, b| a.cmp(b));
+        assert_eq!(arr, expected);
+    }
+}
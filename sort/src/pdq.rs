@@ -0,0 +1,504 @@
+use std::cmp::Ordering;
+
+use crate::heap::heap_observed;
+use crate::insertion::insertion_observed;
+use crate::observe::{Probe, SortObserver};
+
+// 不超过此长度的区间交给插入排序
+const MAX_INSERTION: usize = 20;
+
+// 不短于此长度的区间用九数取中
+const SHORTEST_MEDIAN_OF_MEDIANS: usize = 50;
+
+// 选样本时交换次数达到此值，说明区间多半是降序的
+const MAX_SWAPS: usize = 4 * 3;
+
+// 部分插入排序最多修正的逆序对数
+const MAX_STEPS: usize = 5;
+
+// 短于此长度的区间不值得尝试部分插入排序
+const SHORTEST_SHIFTING: usize = 50;
+
+// 分块切分时每块的长度，偏移量用u8存放
+const BLOCK: usize = 128;
+
+// 模式消除快速排序：
+// 分块切分减少分支预测失败，识别已有序与已切分的区间，
+// 样本太偏时打乱局部模式，退化过深时改用堆排序
+pub fn pdqsort<T: Ord>(arr: &mut [T]) {
+    pdqsort_by(arr, T::cmp);
+}
+
+pub fn pdqsort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    pdqsort_observed(arr, compare, &mut ());
+}
+
+pub fn pdqsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    pdqsort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub(crate) fn pdqsort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    // 允许出现⌊log₂n⌋ + 1次不平衡的切分
+    let limit = usize::BITS - arr.len().leading_zeros();
+    let len = arr.len();
+
+    pdq(
+        arr,
+        0,
+        len,
+        None,
+        limit,
+        &mut compare,
+        &mut Probe::new(observer),
+    );
+}
+
+// 排序arr[lo..hi]；pred为紧邻其左、不大于其中任何元素的样本
+fn pdq<T, F, O>(
+    arr: &mut [T],
+    mut lo: usize,
+    hi: usize,
+    mut pred: Option<usize>,
+    mut limit: u32,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut was_balanced = true;
+    let mut was_partitioned = true;
+    let mut hi = hi;
+
+    loop {
+        let len = hi - lo;
+        let part = &mut arr[lo..hi];
+        let local = &mut probe.at(lo);
+
+        if len <= MAX_INSERTION {
+            insertion_observed(part, &mut *compare, local);
+            return;
+        }
+
+        if limit == 0 {
+            heap_observed(part, &mut *compare, local);
+            return;
+        }
+
+        // 上次切分不平衡，打乱几个元素以破坏可能的模式
+        if !was_balanced {
+            break_patterns(part, local);
+            limit -= 1;
+        }
+
+        let (pivot, likely_sorted) = choose_pivot(part, compare, local);
+
+        // 看起来已经有序，试试能否用少量修正完成
+        if was_balanced
+            && was_partitioned
+            && likely_sorted
+            && partial_insertion_sort(part, compare, local)
+        {
+            return;
+        }
+
+        // 样本与左邻的样本相等，说明区间内有大量重复，
+        // 把等于样本的元素归到左侧后直接跳过
+        if let Some(pred) = pred {
+            probe.compare(pred, lo + pivot);
+            if compare(&arr[pred], &arr[lo + pivot]) != Ordering::Less {
+                let part = &mut arr[lo..hi];
+                lo += partition_equal(part, pivot, compare, &mut probe.at(lo));
+                continue;
+            }
+        }
+
+        let part = &mut arr[lo..hi];
+        let (mid, partitioned) = partition(part, pivot, compare, &mut probe.at(lo));
+        was_balanced = mid.min(len - mid) >= len / 8;
+        was_partitioned = partitioned;
+
+        // 递归较短的一侧，较长的一侧留给循环
+        let pivot = lo + mid;
+        if mid < len - mid {
+            pdq(arr, lo, pivot, pred, limit, compare, probe);
+            lo = pivot + 1;
+            pred = Some(pivot);
+        } else {
+            pdq(arr, pivot + 1, hi, Some(pivot), limit, compare, probe);
+            hi = pivot;
+        }
+    }
+}
+
+// 取四分位附近的样本，返回中位数的下标以及区间是否像是有序
+fn choose_pivot<T, F, O>(part: &mut [T], compare: &mut F, probe: &mut Probe<O>) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = part.len();
+    let (mut a, mut b, mut c) = (len / 4, len / 4 * 2, len / 4 * 3);
+    let mut swaps = 0;
+
+    // 只交换下标，不移动元素
+    let mut sort2 = |a: &mut usize, b: &mut usize| {
+        if probe.cmp(part, *b, *a, compare) == Ordering::Less {
+            std::mem::swap(a, b);
+            swaps += 1;
+        }
+    };
+    let mut sort3 = |a: &mut usize, b: &mut usize, c: &mut usize| {
+        sort2(a, b);
+        sort2(b, c);
+        sort2(a, b);
+    };
+
+    if len >= SHORTEST_MEDIAN_OF_MEDIANS {
+        for x in [&mut a, &mut b, &mut c] {
+            let (mut l, mut r) = (*x - 1, *x + 1);
+            sort3(&mut l, x, &mut r);
+        }
+    }
+
+    sort3(&mut a, &mut b, &mut c);
+
+    if swaps < MAX_SWAPS {
+        (b, swaps == 0)
+    } else {
+        // 处处逆序，多半是降序的，翻转过来
+        for i in 0..len / 2 {
+            probe.swap(part, i, len - 1 - i);
+        }
+        (len - 1 - b, true)
+    }
+}
+
+// 以part[pivot]切分，返回样本的最终下标以及区间原本是否已切分好
+fn partition<T, F, O>(
+    part: &mut [T],
+    pivot: usize,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    probe.swap(part, 0, pivot);
+
+    let len = part.len();
+    let (mut l, mut r) = (1, len);
+
+    // 跳过两端已就位的元素
+    while l < r && probe.cmp(part, l, 0, compare) == Ordering::Less {
+        l += 1;
+    }
+    while l < r && probe.cmp(part, r - 1, 0, compare) != Ordering::Less {
+        r -= 1;
+    }
+
+    let mid = partition_in_blocks(part, l, r, compare, probe);
+    probe.swap(part, 0, mid - 1);
+
+    (mid - 1, l >= r)
+}
+
+// 把part[l..r]中小于part[0]的元素移到前面，返回切分点
+// 左右各扫描一块，记下放错一侧的偏移量，再成对交换
+fn partition_in_blocks<T, F, O>(
+    part: &mut [T],
+    mut l: usize,
+    mut r: usize,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut offsets_l = [0u8; BLOCK];
+    let mut offsets_r = [0u8; BLOCK];
+    let (mut start_l, mut end_l) = (0, 0);
+    let (mut start_r, mut end_r) = (0, 0);
+
+    // 两块互不重叠才能同时使用
+    while r - l >= 2 * BLOCK {
+        if start_l == end_l {
+            start_l = 0;
+            end_l = 0;
+            for i in 0..BLOCK {
+                offsets_l[end_l] = i as u8;
+                end_l += (probe.cmp(part, l + i, 0, compare) != Ordering::Less) as usize;
+            }
+        }
+
+        if start_r == end_r {
+            start_r = 0;
+            end_r = 0;
+            for i in 0..BLOCK {
+                offsets_r[end_r] = i as u8;
+                end_r += (probe.cmp(part, r - 1 - i, 0, compare) == Ordering::Less) as usize;
+            }
+        }
+
+        let count = (end_l - start_l).min(end_r - start_r);
+        for k in 0..count {
+            let i = l + offsets_l[start_l + k] as usize;
+            let j = r - 1 - offsets_r[start_r + k] as usize;
+            probe.swap(part, i, j);
+        }
+        start_l += count;
+        start_r += count;
+
+        if start_l == end_l {
+            l += BLOCK;
+        }
+        if start_r == end_r {
+            r -= BLOCK;
+        }
+    }
+
+    // 余下不足两块，逐个切分；未处理完的那块重新扫描即可
+    loop {
+        while l < r && probe.cmp(part, l, 0, compare) == Ordering::Less {
+            l += 1;
+        }
+        while l < r && probe.cmp(part, r - 1, 0, compare) != Ordering::Less {
+            r -= 1;
+        }
+
+        if l >= r {
+            return l;
+        }
+
+        probe.swap(part, l, r - 1);
+        l += 1;
+        r -= 1;
+    }
+}
+
+// 区间内没有小于样本的元素，把等于样本的元素移到左侧，返回它们的个数
+fn partition_equal<T, F, O>(
+    part: &mut [T],
+    pivot: usize,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    probe.swap(part, 0, pivot);
+
+    let (mut l, mut r) = (1, part.len());
+
+    loop {
+        while l < r && probe.cmp(part, 0, l, compare) != Ordering::Less {
+            l += 1;
+        }
+        while l < r && probe.cmp(part, 0, r - 1, compare) == Ordering::Less {
+            r -= 1;
+        }
+
+        if l >= r {
+            return l;
+        }
+
+        probe.swap(part, l, r - 1);
+        l += 1;
+        r -= 1;
+    }
+}
+
+// 修正至多MAX_STEPS处相邻逆序，返回区间是否因此有序
+fn partial_insertion_sort<T, F, O>(part: &mut [T], compare: &mut F, probe: &mut Probe<O>) -> bool
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let len = part.len();
+    let mut i = 1;
+
+    for _ in 0..MAX_STEPS {
+        while i < len && probe.cmp(part, i, i - 1, compare) != Ordering::Less {
+            i += 1;
+        }
+
+        if i == len {
+            return true;
+        }
+
+        if len < SHORTEST_SHIFTING {
+            return false;
+        }
+
+        probe.swap(part, i - 1, i);
+
+        // 换到左边的元素继续左移，换到右边的继续右移
+        let mut j = i - 1;
+        while j > 0 && probe.cmp(part, j, j - 1, compare) == Ordering::Less {
+            probe.swap(part, j, j - 1);
+            j -= 1;
+        }
+
+        let mut j = i;
+        while j + 1 < len && probe.cmp(part, j + 1, j, compare) == Ordering::Less {
+            probe.swap(part, j, j + 1);
+            j += 1;
+        }
+    }
+
+    false
+}
+
+// 用以长度为种子的xorshift打乱中部的几个元素，结果是确定的
+fn break_patterns<T, O>(part: &mut [T], probe: &mut Probe<O>)
+where
+    O: SortObserver + ?Sized,
+{
+    let len = part.len();
+    let mut seed = len as u64;
+    let mut next = || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed as usize
+    };
+
+    let mask = len.next_power_of_two() - 1;
+    let pos = len / 4 * 2;
+
+    for i in 0..3 {
+        let mut other = next() & mask;
+        if other >= len {
+            other -= len;
+        }
+        probe.swap(part, pos - 1 + i, other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::{pdqsort, pdqsort_by, pdqsort_by_key};
+    use crate::observe::{Counter, Probe};
+
+    fn check(mut arr: Vec<u32>) {
+        let mut expected = arr.clone();
+        expected.sort();
+
+        pdqsort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    fn comparisons(mut arr: Vec<u32>) -> usize {
+        let mut counter = Counter::default();
+        super::pdqsort_observed(&mut arr, u32::cmp, &mut counter);
+        assert!(arr.is_sorted());
+
+        counter.comparisons
+    }
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+        pdqsort(&mut arr);
+    }
+
+    #[test]
+    fn basic() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+        pdqsort(&mut arr);
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7, 8, 9, 10, 13, 14, 16, 17]);
+    }
+
+    #[test]
+    fn random_input() {
+        let mut rng = rand::thread_rng();
+
+        for len in [21, 100, 1000, 10000] {
+            check((0..len).map(|_| rng.gen_range(0..1 << 20)).collect());
+            check((0..len).map(|_| rng.gen_range(0..4)).collect());
+        }
+    }
+
+    #[test]
+    fn patterns() {
+        let len = 5000;
+
+        check(vec![42; len]);
+        check((0..len as u32).map(|i| i % 700).collect());
+        check(
+            (0..len as u32 / 2)
+                .chain((0..len as u32 / 2).rev())
+                .collect(),
+        );
+        check((0..len as u32 / 2).rev().chain(0..len as u32 / 2).collect());
+
+        let mut almost: Vec<u32> = (0..len as u32).collect();
+        almost.swap(10, 4000);
+        almost.swap(2000, 2001);
+        check(almost);
+    }
+
+    // 有序与降序输入只需线性次比较
+    #[test]
+    fn presorted() {
+        assert!(comparisons((0..10000).collect()) < 10000 + 32);
+        assert!(comparisons((0..10000).rev().collect()) < 10000 + 32);
+    }
+
+    #[test]
+    fn heap_fallback() {
+        let mut arr: Vec<u32> = (0..100).map(|i| (i * 37) % 101).collect();
+        let len = arr.len();
+
+        super::pdq(
+            &mut arr,
+            0,
+            len,
+            None,
+            0,
+            &mut u32::cmp,
+            &mut Probe::new(&mut ()),
+        );
+        assert!(arr.is_sorted());
+    }
+
+    #[test]
+    fn reverse() {
+        let mut arr: Vec<u32> = (0..1000).map(|i| (i * 7919) % 1009).collect();
+        pdqsort_by(&mut arr, |a, b| b.cmp(a));
+        assert!(arr.iter().rev().is_sorted());
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr: Vec<(u32, u32)> = (0..1000).map(|i| (i % 7, i)).collect();
+        pdqsort_by_key(&mut arr, |&(k, _)| k);
+        assert!(arr.is_sorted_by_key(|&(k, _)| k));
+    }
+
+    #[test]
+    fn strings() {
+        let mut arr: Vec<String> = (0..3000).map(|i| ((i * 7919) % 1009).to_string()).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        pdqsort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+}
@@ -1,53 +1,269 @@
-use rand::seq::SliceRandom;
+use rand::Rng;
 use rand::thread_rng;
 use std::cmp::Ordering;
 
-pub fn qsort<T: Ord + Clone>(arr: &mut [T]) {
+use crate::observe::{Probe, SortObserver};
+
+// 样本的选取策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pivot {
+    // 首元素
+    First,
+    // 首、中、尾三数取中
+    MedianOfThree,
+    // Tukey九数取中：三组三数取中后再取中，短区间退化为三数取中
+    Ninther,
+    // 随机下标
+    Random,
+}
+
+impl Pivot {
+    // 返回样本的下标，part不得为空
+    pub(crate) fn select<T, R, F, O>(
+        self,
+        part: &[T],
+        rng: &mut R,
+        compare: &mut F,
+        probe: &mut Probe<O>,
+    ) -> usize
+    where
+        R: Rng + ?Sized,
+        F: FnMut(&T, &T) -> Ordering,
+        O: SortObserver + ?Sized,
+    {
+        let len = part.len();
+        let (mid, last) = (len / 2, len - 1);
+        let mut median = |a, b, c| median_of_three(part, [a, b, c], compare, probe);
+
+        match self {
+            Pivot::First => 0,
+            Pivot::MedianOfThree => median(0, mid, last),
+            Pivot::Ninther if len >= 40 => {
+                let step = len / 8;
+                let a = median(0, step, 2 * step);
+                let b = median(mid - step, mid, mid + step);
+                let c = median(last - 2 * step, last - step, last);
+                median(a, b, c)
+            }
+            Pivot::Ninther => median(0, mid, last),
+            Pivot::Random => rng.gen_range(0..len),
+        }
+    }
+}
+
+pub fn qsort<T: Ord>(arr: &mut [T]) {
+    qsort_by(arr, T::cmp);
+}
+
+pub fn qsort_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    qsort_observed(arr, compare, &mut ());
+}
+
+pub fn qsort_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    qsort_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub(crate) fn qsort_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+
     // 打乱数组，防止最坏情况出现
-    let mut rng = thread_rng();
-    arr.shuffle(&mut rng);
+    shuffle(arr, &mut thread_rng(), &mut probe);
+
+    quick(arr, &mut compare, &mut probe);
+}
+
+// 由调用者提供随机源与样本策略，相同的种子总得到相同的过程
+pub fn qsort_with<T, R>(arr: &mut [T], pivot: Pivot, rng: &mut R)
+where
+    T: Ord,
+    R: Rng + ?Sized,
+{
+    qsort_with_by(arr, pivot, rng, T::cmp);
+}
+
+pub fn qsort_with_by<T, R, F>(arr: &mut [T], pivot: Pivot, rng: &mut R, compare: F)
+where
+    R: Rng + ?Sized,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    qsort_with_observed(arr, pivot, rng, compare, &mut ());
+}
 
-    quick(arr);
+pub fn qsort_with_by_key<T, R, K, F>(arr: &mut [T], pivot: Pivot, rng: &mut R, mut f: F)
+where
+    R: Rng + ?Sized,
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    qsort_with_by(arr, pivot, rng, |a, b| f(a).cmp(&f(b)));
 }
 
-fn quick<T: Ord + Clone>(part: &mut [T]) {
+pub fn qsort_with_observed<T, R, F, O>(
+    arr: &mut [T],
+    pivot: Pivot,
+    rng: &mut R,
+    mut compare: F,
+    observer: &mut O,
+) where
+    R: Rng + ?Sized,
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    quick_with(arr, pivot, rng, &mut compare, &mut Probe::new(observer));
+}
+
+// Fisher-Yates洗牌
+pub(crate) fn shuffle<T, R, O>(arr: &mut [T], rng: &mut R, probe: &mut Probe<O>)
+where
+    R: Rng + ?Sized,
+    O: SortObserver + ?Sized,
+{
+    for i in (1..arr.len()).rev() {
+        let j = rng.gen_range(0..=i);
+        probe.swap(arr, i, j);
+    }
+}
+
+fn quick_with<T, R, F, O>(
+    mut part: &mut [T],
+    pivot: Pivot,
+    rng: &mut R,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) where
+    R: Rng + ?Sized,
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    // 不洗牌，样本可能一直很差；递归较短的一侧，较长的一侧留给循环，栈深不超过 log n
+    while part.len() > 1 {
+        let sample = pivot.select(part, rng, compare, probe);
+        probe.swap(part, 0, sample);
+
+        let (lt, gt) = partition(part, compare, probe);
+        let (left, rest) = part.split_at_mut(lt);
+        let right = &mut rest[(gt - lt)..];
+
+        if left.len() < right.len() {
+            quick_with(left, pivot, rng, compare, &mut probe.at(0));
+            probe.advance(gt);
+            part = right;
+        } else {
+            quick_with(right, pivot, rng, compare, &mut probe.at(gt));
+            part = left;
+        }
+    }
+}
+
+pub(crate) fn quick<T, F, O>(part: &mut [T], compare: &mut F, probe: &mut Probe<O>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     if part.len() <= 1 {
         return;
     }
 
-    let mut lt = 0; // 区间[0, lt)的元素都小于sample
-    let mut idx = 1; // 待比较游标，不是相等游标！[lt, gt]才是相等区间
-    let mut gt = part.len() - 1; // 区间(gt, len)的元素都大于sample
-    let sample = part[0].clone();
+    let (lt, gt) = partition(part, compare, probe);
 
-    // 内循环结束时，idx == gt + 1
-    while idx <= gt {
-        match part[idx].cmp(&sample) {
+    // 相等区间(含有样本元素)不再参与递归
+    quick(&mut part[..lt], compare, &mut probe.at(0));
+    quick(&mut part[gt..], compare, &mut probe.at(gt));
+}
+
+// 以首元素为样本三向切分，part不得为空
+// 返回(lt, gt)：[0, lt)较小，[lt, gt)相等，[gt, len)较大
+pub(crate) fn partition<T, F, O>(
+    part: &mut [T],
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) -> (usize, usize)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    // 样本留在首位，只划分其后的部分
+    let mut lt = 1; // 区间[1, lt)的元素都小于sample
+    let mut idx = 1; // 待比较游标，不是相等游标！[lt, gt)才是相等区间
+    let mut gt = part.len(); // 区间[gt, len)的元素都大于sample
+
+    // 内循环结束时，idx == gt
+    while idx < gt {
+        match probe.cmp(part, idx, 0, compare) {
             // lt与idx拉开距离也不要紧，
             // 此交换总会拉取更小的元素到lt位置上
             Ordering::Less => {
-                part.swap(lt, idx);
+                probe.swap(part, lt, idx);
                 lt += 1;
                 idx += 1;
             }
 
             Ordering::Greater => {
-                part.swap(idx, gt);
                 gt -= 1;
+                probe.swap(part, idx, gt);
             }
 
             Ordering::Equal => idx += 1,
         }
     }
 
-    // 相等区间(含有样本元素)不再参与递归
-    quick(&mut part[0..lt]);
-    quick(&mut part[(gt + 1)..]);
+    // 样本与最后一个较小元素交换，
+    // 此时[0, lt - 1)较小，[lt - 1, gt)相等，[gt, len)较大
+    probe.swap(part, 0, lt - 1);
+
+    (lt - 1, gt)
+}
+
+// 三数取中，返回中位数的下标
+pub(crate) fn median_of_three<T, F, O>(
+    part: &[T],
+    [a, b, c]: [usize; 3],
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut less = |x, y| probe.cmp(part, x, y, compare) == Ordering::Less;
+
+    let (a, b) = if less(b, a) { (b, a) } else { (a, b) };
+
+    if less(c, a) {
+        a
+    } else if less(c, b) {
+        c
+    } else {
+        b
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::qsort;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::{
+        Pivot, qsort, qsort_by, qsort_by_key, qsort_with, qsort_with_by, qsort_with_by_key,
+    };
+
+    const PIVOTS: [Pivot; 4] = [
+        Pivot::First,
+        Pivot::MedianOfThree,
+        Pivot::Ninther,
+        Pivot::Random,
+    ];
 
     #[test]
     fn test_empty() {
@@ -73,4 +289,95 @@ mod tests {
 
         assert_eq!(String::from_iter(arr), "BBBRRRRRWWWW");
     }
+
+    #[test]
+    fn test_strings() {
+        let mut arr = ["pear", "apple", "fig", "kiwi", "banana", "fig"].map(String::from);
+
+        qsort(&mut arr);
+
+        assert_eq!(arr, ["apple", "banana", "fig", "fig", "kiwi", "pear"]);
+    }
+
+    #[test]
+    fn test_reverse() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+
+        qsort_by(&mut arr, |a, b| b.cmp(a));
+
+        assert_eq!(arr, [17, 16, 14, 13, 10, 9, 8, 7, 6, 5, 4, 3, 2]);
+    }
+
+    #[test]
+    fn test_float_key() {
+        let mut arr = [2.5, -1.0, 0.5, 3.0];
+
+        qsort_by(&mut arr, f64::total_cmp);
+
+        assert_eq!(arr, [-1.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn test_by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+
+        qsort_by_key(&mut arr, |&(k, _)| k);
+
+        assert_eq!(arr.map(|(k, _)| k), [1, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_pivots() {
+        let arr: Vec<u32> = (0..200).map(|i| (i * 7919) % 211).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        for pivot in PIVOTS {
+            let mut arr = arr.clone();
+            qsort_with(&mut arr, pivot, &mut StdRng::seed_from_u64(42));
+            assert_eq!(arr, expected, "{pivot:?}");
+        }
+    }
+
+    // 逆序输入配合首元素样本，每次切分只剥掉一个元素；
+    // 在小栈上运行，递归深度随长度线性增长时必然溢出
+    #[test]
+    fn test_first_pivot_reversed() {
+        std::thread::Builder::new()
+            .stack_size(128 << 10)
+            .spawn(|| {
+                let mut arr: Vec<u64> = (0..5000).rev().collect();
+
+                qsort_with(&mut arr, Pivot::First, &mut StdRng::seed_from_u64(1));
+
+                assert!(arr.iter().copied().eq(0..5000));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_replay() {
+        let arr: Vec<(u32, u32)> = (0..200).map(|i| (i % 5, i)).collect();
+
+        for pivot in PIVOTS {
+            let mut first = arr.clone();
+            let mut second = arr.clone();
+
+            qsort_with_by(&mut first, pivot, &mut StdRng::seed_from_u64(7), |a, b| {
+                a.0.cmp(&b.0)
+            });
+            // 按键比较与直接比较的过程相同
+            qsort_with_by_key(
+                &mut second,
+                pivot,
+                &mut StdRng::seed_from_u64(7),
+                |&(k, _)| k,
+            );
+
+            assert!(first.is_sorted_by_key(|&(k, _)| k));
+            assert_eq!(first, second, "{pivot:?}");
+        }
+    }
 }
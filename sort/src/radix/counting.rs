@@ -0,0 +1,121 @@
+use super::{RadixKey, SortKey, distribute, lsd_radix_sort_observed};
+use crate::observe::{Probe, SortObserver};
+
+// 计数排序，稳定；键的取值范围远大于元素个数时改用低位优先的基数排序
+pub fn counting_sort<T: SortKey>(arr: &mut [T]) {
+    counting_sort_by_key(arr, T::sort_key);
+}
+
+pub fn counting_sort_by_key<T, K, F>(arr: &mut [T], f: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    counting_sort_observed(arr, f, &mut ());
+}
+
+// 不比较元素，只上报交换
+pub fn counting_sort_observed<T, K, F, O>(arr: &mut [T], mut f: F, observer: &mut O)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+    O: SortObserver + ?Sized,
+{
+    let keys: Vec<u128> = arr.iter().map(|x| f(x).ordered()).collect();
+
+    let (Some(&min), Some(&max)) = (keys.iter().min(), keys.iter().max()) else {
+        return;
+    };
+
+    // 桶数组按键的范围分配，稀疏的大范围键会耗尽内存
+    if max - min >= 4 * arr.len().max(256) as u128 {
+        lsd_radix_sort_observed(arr, f, observer);
+        return;
+    }
+
+    let range = (max - min) as usize + 1;
+    let buckets: Vec<usize> = keys.iter().map(|&k| (k - min) as usize).collect();
+
+    distribute(arr, &buckets, range, &mut Probe::new(observer));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{counting_sort, counting_sort_by_key};
+    use crate::radix::SortKey;
+
+    #[test]
+    fn empty() {
+        let mut arr: [u32; 0] = [];
+        counting_sort(&mut arr);
+    }
+
+    #[test]
+    fn basic() {
+        let mut arr = [7, 5, 9, 8, 2, 4, 3, 10, 16, 13, 17, 14, 6u32];
+        counting_sort(&mut arr);
+        assert_eq!(arr, [2, 3, 4, 5, 6, 7, 8, 9, 10, 13, 14, 16, 17]);
+    }
+
+    #[test]
+    fn signed() {
+        let mut arr = [3i8, -128, 0, 127, -1, 3];
+        counting_sort(&mut arr);
+        assert_eq!(arr, [-128, -1, 0, 3, 3, 127]);
+    }
+
+    #[test]
+    fn stable() {
+        let mut arr = [(3u8, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        counting_sort_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr, [(1, 'b'), (1, 'd'), (2, 'c'), (3, 'a')]);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Task {
+        priority: u16,
+        name: String,
+    }
+
+    impl SortKey for Task {
+        type Key = u16;
+
+        fn sort_key(&self) -> u16 {
+            self.priority
+        }
+    }
+
+    #[test]
+    fn sort_key() {
+        let task = |priority, name: &str| Task {
+            priority,
+            name: name.to_owned(),
+        };
+        let mut arr = [task(2, "b"), task(0, "a"), task(1, "c")];
+
+        counting_sort(&mut arr);
+        assert_eq!(arr, [task(0, "a"), task(1, "c"), task(2, "b")]);
+    }
+
+    #[test]
+    fn sparse() {
+        let mut arr = [u32::MAX, 0, 7, u32::MAX - 1, 7];
+        counting_sort(&mut arr);
+        assert_eq!(arr, [0, 7, 7, u32::MAX - 1, u32::MAX]);
+
+        let mut arr = [(u64::MAX, 'a'), (3, 'b'), (u64::MAX, 'c'), (3, 'd')];
+        counting_sort_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr, [(3, 'b'), (3, 'd'), (u64::MAX, 'a'), (u64::MAX, 'c')]);
+    }
+
+    #[test]
+    fn full_range() {
+        let mut arr = [u128::MAX, 0, i128::MAX as u128];
+        counting_sort(&mut arr);
+        assert_eq!(arr, [0, i128::MAX as u128, u128::MAX]);
+
+        let mut arr = [i128::MAX, i128::MIN, 0];
+        counting_sort(&mut arr);
+        assert_eq!(arr, [i128::MIN, 0, i128::MAX]);
+    }
+}
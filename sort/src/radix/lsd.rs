@@ -0,0 +1,80 @@
+use super::{RadixKey, SortKey, distribute};
+use crate::observe::{Probe, SortObserver};
+
+// 低位优先的基数排序，稳定；逐字节做计数分配
+pub fn lsd_radix_sort<T: SortKey>(arr: &mut [T]) {
+    lsd_radix_sort_by_key(arr, T::sort_key);
+}
+
+pub fn lsd_radix_sort_by_key<T, K, F>(arr: &mut [T], f: F)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+{
+    lsd_radix_sort_observed(arr, f, &mut ());
+}
+
+// 不比较元素，只上报交换
+pub fn lsd_radix_sort_observed<T, K, F, O>(arr: &mut [T], mut f: F, observer: &mut O)
+where
+    K: RadixKey,
+    F: FnMut(&T) -> K,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+    let mut buckets = Vec::with_capacity(arr.len());
+
+    for i in 0..K::BYTES {
+        buckets.clear();
+        buckets.extend(arr.iter().map(|x| f(x).byte(i) as usize));
+
+        // 该字节全都相同，这一趟分配什么也不会改变
+        if buckets.windows(2).all(|w| w[0] == w[1]) {
+            continue;
+        }
+
+        distribute(arr, &buckets, 256, &mut probe);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lsd_radix_sort, lsd_radix_sort_by_key};
+
+    #[test]
+    fn empty() {
+        let mut arr: [u64; 0] = [];
+        lsd_radix_sort(&mut arr);
+    }
+
+    #[test]
+    fn unsigned() {
+        let mut arr: Vec<u64> = (0..1000u64)
+            .map(|i| i.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+            .collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        lsd_radix_sort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    #[test]
+    fn signed() {
+        let mut arr = [i32::MAX, -5, 0, i32::MIN, 1 << 20, -(1 << 20), 7];
+        lsd_radix_sort(&mut arr);
+        assert_eq!(arr, [i32::MIN, -(1 << 20), -5, 0, 7, 1 << 20, i32::MAX]);
+    }
+
+    #[test]
+    fn stable() {
+        let mut arr: Vec<(u32, usize)> = (0..500)
+            .map(|i| ((i * 7919 % 13) << 16, i as usize))
+            .collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        lsd_radix_sort_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr, expected);
+    }
+}
@@ -0,0 +1,118 @@
+mod counting;
+mod lsd;
+mod msd;
+
+use crate::observe::{Probe, SortObserver};
+
+pub use self::counting::{counting_sort, counting_sort_by_key, counting_sort_observed};
+pub use self::lsd::{lsd_radix_sort, lsd_radix_sort_by_key, lsd_radix_sort_observed};
+pub use self::msd::{msd_radix_sort, msd_radix_sort_by_key, msd_radix_sort_observed};
+
+// 定宽整数键
+pub trait RadixKey: Copy {
+    const BYTES: usize;
+
+    // 保序地映射为无符号数：有符号数翻转符号位
+    fn ordered(self) -> u128;
+
+    // 映射后自低位数起的第i个字节
+    fn byte(self, i: usize) -> u8 {
+        (self.ordered() >> (8 * i)) as u8
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl RadixKey for $ty {
+                const BYTES: usize = size_of::<$ty>();
+
+                fn ordered(self) -> u128 {
+                    self as u128
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_signed {
+    ($($ty:ty => $unsigned:ty),*) => {
+        $(
+            impl RadixKey for $ty {
+                const BYTES: usize = size_of::<$ty>();
+
+                fn ordered(self) -> u128 {
+                    ((self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1))) as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+// 从元素中取出整数键，结构体实现它即可按某个整数字段排序
+pub trait SortKey {
+    type Key: RadixKey;
+
+    fn sort_key(&self) -> Self::Key;
+}
+
+impl<K: RadixKey> SortKey for K {
+    type Key = K;
+
+    fn sort_key(&self) -> K {
+        *self
+    }
+}
+
+// 按 dest 把元素挪到目标位置：arr[i] 最终位于 dest[i]
+// 沿置换环交换，不需要 Clone
+fn permute<T, O>(arr: &mut [T], dest: &mut [usize], probe: &mut Probe<O>)
+where
+    O: SortObserver + ?Sized,
+{
+    for i in 0..arr.len() {
+        while dest[i] != i {
+            let j = dest[i];
+            probe.swap(arr, i, j);
+            dest.swap(i, j);
+        }
+    }
+}
+
+// 稳定的计数分配：buckets 为每个元素所属的桶
+// 返回每个桶的起点，末尾附上len
+fn distribute<T, O>(
+    arr: &mut [T],
+    buckets: &[usize],
+    bucket_count: usize,
+    probe: &mut Probe<O>,
+) -> Vec<usize>
+where
+    O: SortObserver + ?Sized,
+{
+    let mut starts = vec![0; bucket_count + 1];
+
+    for &b in buckets {
+        starts[b + 1] += 1;
+    }
+
+    for b in 0..bucket_count {
+        starts[b + 1] += starts[b];
+    }
+
+    let mut next = starts.clone();
+    let mut dest: Vec<usize> = buckets
+        .iter()
+        .map(|&b| {
+            next[b] += 1;
+            next[b] - 1
+        })
+        .collect();
+
+    permute(arr, &mut dest, probe);
+
+    starts
+}
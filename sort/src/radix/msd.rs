@@ -0,0 +1,147 @@
+use super::distribute;
+use crate::insertion::insertion_observed;
+use crate::observe::{Probe, SortObserver};
+
+// 小于此长度的桶交给插入排序
+const INSERTION_THRESHOLD: usize = 16;
+
+// 高位优先的基数排序，适用于字节串，稳定
+pub fn msd_radix_sort<T: AsRef<[u8]>>(arr: &mut [T]) {
+    msd_radix_sort_by_key(arr, T::as_ref);
+}
+
+pub fn msd_radix_sort_by_key<T, F>(arr: &mut [T], f: F)
+where
+    F: Fn(&T) -> &[u8],
+{
+    msd_radix_sort_observed(arr, f, &mut ());
+}
+
+// 比较只发生在桶内的插入排序中
+pub fn msd_radix_sort_observed<T, F, O>(arr: &mut [T], f: F, observer: &mut O)
+where
+    F: Fn(&T) -> &[u8],
+    O: SortObserver + ?Sized,
+{
+    msd(arr, 0, &f, &mut Probe::new(observer));
+}
+
+fn msd<T, F, O>(mut part: &mut [T], mut depth: usize, f: &F, probe: &mut Probe<O>)
+where
+    F: Fn(&T) -> &[u8],
+    O: SortObserver + ?Sized,
+{
+    loop {
+        if part.len() <= INSERTION_THRESHOLD {
+            // 前depth个字节都相同，只比较剩下的部分
+            insertion_observed(part, |a, b| f(a)[depth..].cmp(&f(b)[depth..]), probe);
+            return;
+        }
+
+        // 0号桶放已经结束的串，其余按字节值+1入桶
+        let buckets: Vec<usize> = part
+            .iter()
+            .map(|x| f(x).get(depth).map_or(0, |&b| b as usize + 1))
+            .collect();
+        let starts = distribute(part, &buckets, 257, probe);
+
+        // 已结束的串全部相等，无需再排
+        // 最大的桶留给循环，其余的桶不超过一半，栈深不超过 log n；
+        // 长公共前缀只会让循环多转几圈
+        let largest = (1..257).max_by_key(|&b| starts[b + 1] - starts[b]).unwrap();
+        for b in (1..257).filter(|&b| b != largest) {
+            if starts[b + 1] - starts[b] > 1 {
+                msd(
+                    &mut part[starts[b]..starts[b + 1]],
+                    depth + 1,
+                    f,
+                    &mut probe.at(starts[b]),
+                );
+            }
+        }
+
+        probe.advance(starts[largest]);
+        part = &mut part[starts[largest]..starts[largest + 1]];
+        depth += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{msd_radix_sort, msd_radix_sort_by_key};
+
+    #[test]
+    fn empty() {
+        let mut arr: [&str; 0] = [];
+        msd_radix_sort(&mut arr);
+    }
+
+    #[test]
+    fn strings() {
+        let mut arr = [
+            "she",
+            "sells",
+            "seashells",
+            "by",
+            "the",
+            "sea",
+            "shore",
+            "",
+            "s",
+        ]
+        .map(String::from);
+
+        msd_radix_sort(&mut arr);
+        assert_eq!(
+            arr,
+            [
+                "",
+                "by",
+                "s",
+                "sea",
+                "seashells",
+                "sells",
+                "she",
+                "shore",
+                "the"
+            ]
+        );
+    }
+
+    #[test]
+    fn many() {
+        let mut arr: Vec<Vec<u8>> = (0..2000u32)
+            .map(|i| (i * 7919 % 1013).to_string().into_bytes())
+            .collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        msd_radix_sort(&mut arr);
+        assert_eq!(arr, expected);
+    }
+
+    // 公共前缀很长，逐字节递归会耗尽栈
+    #[test]
+    fn long_common_prefix() {
+        let prefix = vec![b'x'; 100_000];
+        let mut arr: Vec<Vec<u8>> = (0..20u8)
+            .map(|i| [&prefix[..], &[i * 7 % 20]].concat())
+            .chain((0..2000).map(|i| vec![b'a'; i * 7 % 2000]))
+            .collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        msd_radix_sort(&mut arr);
+        assert!(arr == expected);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr: Vec<(String, usize)> = (0..200).map(|i| (format!("k{}", i % 17), i)).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        msd_radix_sort_by_key(&mut arr, |(k, _)| k.as_bytes());
+        assert_eq!(arr, expected);
+    }
+}
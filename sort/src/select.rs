@@ -0,0 +1,314 @@
+use std::cmp::Ordering;
+
+use crate::heap::{heap_observed, sink, swim};
+use crate::insertion::insertion_observed;
+use crate::observe::{Probe, SortObserver};
+use crate::quick::{median_of_three, partition};
+
+// 小于此长度的区间直接插入排序
+const INSERTION_THRESHOLD: usize = 16;
+
+// 快速选择：重排数组，使下标n处恰为排序后的第n个元素，
+// 其左侧不大于它，右侧不小于它；n越界时恐慌
+pub fn select_nth<T: Ord>(arr: &mut [T], n: usize) -> (&mut [T], &mut T, &mut [T]) {
+    select_nth_by(arr, n, T::cmp)
+}
+
+pub fn select_nth_by<T, F>(arr: &mut [T], n: usize, compare: F) -> (&mut [T], &mut T, &mut [T])
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    select_nth_observed(arr, n, compare, &mut ())
+}
+
+pub fn select_nth_by_key<T, K, F>(arr: &mut [T], n: usize, mut f: F) -> (&mut [T], &mut T, &mut [T])
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    select_nth_by(arr, n, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn select_nth_observed<'a, T, F, O>(
+    arr: &'a mut [T],
+    n: usize,
+    mut compare: F,
+    observer: &mut O,
+) -> (&'a mut [T], &'a mut T, &'a mut [T])
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    assert!(
+        n < arr.len(),
+        "index {n} out of range for length {}",
+        arr.len()
+    );
+
+    select(arr, n, &mut compare, &mut Probe::new(observer));
+
+    let (left, rest) = arr.split_at_mut(n);
+    let (nth, right) = rest.split_first_mut().unwrap();
+    (left, nth, right)
+}
+
+// 只在包含n的一侧继续切分；切分太差时退回堆排序，即introselect
+fn select<T, F, O>(part: &mut [T], n: usize, compare: &mut F, probe: &mut Probe<O>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let limit = 2 * part.len().max(1).ilog2();
+    select_with_limit(part, n, limit, compare, probe);
+}
+
+// limit为还允许切分的次数，用尽后退回堆排序
+fn select_with_limit<T, F, O>(
+    mut part: &mut [T],
+    mut n: usize,
+    mut limit: u32,
+    compare: &mut F,
+    probe: &mut Probe<O>,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    loop {
+        let len = part.len();
+
+        if len <= INSERTION_THRESHOLD {
+            insertion_observed(part, &mut *compare, probe);
+            return;
+        }
+
+        if limit == 0 {
+            heap_observed(part, &mut *compare, probe);
+            return;
+        }
+        limit -= 1;
+
+        let pivot = median_of_three(part, [0, len / 2, len - 1], compare, probe);
+        probe.swap(part, 0, pivot);
+
+        let (lt, gt) = partition(part, compare, probe);
+
+        if n < lt {
+            part = &mut part[..lt];
+        } else if n >= gt {
+            part = &mut part[gt..];
+            n -= gt;
+            probe.advance(gt);
+        } else {
+            // 落在相等区间
+            return;
+        }
+    }
+}
+
+// 部分排序：前k个位置依次放入最小的k个元素，其余元素次序不定
+pub fn partial_sort<T: Ord>(arr: &mut [T], k: usize) {
+    partial_sort_by(arr, k, T::cmp);
+}
+
+pub fn partial_sort_by<T, F>(arr: &mut [T], k: usize, compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    partial_sort_observed(arr, k, compare, &mut ());
+}
+
+pub fn partial_sort_observed<T, F, O>(arr: &mut [T], k: usize, mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let compare = &mut compare;
+    let probe = &mut Probe::new(observer);
+    let k = k.min(arr.len());
+
+    if k == 0 {
+        return;
+    }
+
+    // 前k个元素构建大顶堆
+    for parent in (0..k / 2).rev() {
+        sink(&mut arr[..k], parent, compare, probe);
+    }
+
+    // 比堆顶小的元素换入堆中
+    for i in k..arr.len() {
+        if probe.cmp(arr, i, 0, compare) == Ordering::Less {
+            probe.swap(arr, 0, i);
+            sink(&mut arr[..k], 0, compare, probe);
+        }
+    }
+
+    // 堆中即最小的k个元素
+    for end in (1..k).rev() {
+        probe.swap(arr, 0, end);
+        sink(&mut arr[..end], 0, compare, probe);
+    }
+}
+
+pub fn partial_sort_by_key<T, K, F>(arr: &mut [T], k: usize, mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    partial_sort_by(arr, k, |a, b| f(a).cmp(&f(b)));
+}
+
+// 从迭代器中取出最小的k个元素，升序返回；只占用O(k)的空间
+pub fn top_k<I>(iter: I, k: usize) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    top_k_by(iter, k, I::Item::cmp)
+}
+
+pub fn top_k_by<I, F>(iter: I, k: usize, mut compare: F) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item, &I::Item) -> Ordering,
+{
+    // k可能远大于元素个数，不按k预留空间
+    let mut heap = Vec::new();
+
+    if k == 0 {
+        return heap;
+    }
+
+    for x in iter {
+        if heap.len() < k {
+            heap.push(x);
+            let last = heap.len() - 1;
+            swim(&mut heap, last, &mut compare);
+        } else if compare(&x, &heap[0]) == Ordering::Less {
+            heap[0] = x;
+            sink(&mut heap, 0, &mut compare, &mut Probe::new(&mut ()));
+        }
+    }
+
+    for end in (1..heap.len()).rev() {
+        heap.swap(0, end);
+        sink(&mut heap[..end], 0, &mut compare, &mut Probe::new(&mut ()));
+    }
+
+    heap
+}
+
+pub fn top_k_by_key<I, K, F>(iter: I, k: usize, mut f: F) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    K: Ord,
+    F: FnMut(&I::Item) -> K,
+{
+    top_k_by(iter, k, |a, b| f(a).cmp(&f(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shuffled(len: u32) -> Vec<u32> {
+        (0..len).map(|i| (i * 7919) % len).collect()
+    }
+
+    #[test]
+    fn select_every_index() {
+        let arr: Vec<u32> = (0..100).map(|i| (i * 7919) % 37).collect();
+        let mut sorted = arr.clone();
+        sorted.sort();
+
+        for (n, &expected) in sorted.iter().enumerate() {
+            let mut arr = arr.clone();
+            let (left, &mut nth, right) = select_nth(&mut arr, n);
+
+            assert_eq!(nth, expected);
+            assert!(left.iter().all(|&x| x <= nth));
+            assert!(right.iter().all(|&x| x >= nth));
+        }
+    }
+
+    #[test]
+    fn median() {
+        let mut arr = shuffled(1001);
+        let (_, median, _) = select_nth(&mut arr, 500);
+        assert_eq!(*median, 500);
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_out_of_range() {
+        let mut arr = [1, 2, 3];
+        select_nth(&mut arr, 3);
+    }
+
+    #[test]
+    fn select_by_key() {
+        let mut arr: Vec<(u32, char)> = shuffled(50).into_iter().map(|i| (i, 'x')).collect();
+        let (_, nth, _) = select_nth_by_key(&mut arr, 10, |&(k, _)| k);
+        assert_eq!(nth.0, 10);
+
+        let (_, nth, _) = select_nth_by(&mut arr, 10, |a, b| b.cmp(a));
+        assert_eq!(nth.0, 39);
+    }
+
+    #[test]
+    fn select_heap_fallback() {
+        // 不允许切分，直接退回堆排序
+        for n in [0, 42, 99] {
+            let mut arr = shuffled(100);
+            select_with_limit(&mut arr, n, 0, &mut u32::cmp, &mut Probe::new(&mut ()));
+            assert_eq!(arr, (0..100).collect::<Vec<_>>());
+        }
+
+        // 切分一次后用尽
+        let mut arr = shuffled(100);
+        select_with_limit(&mut arr, 42, 1, &mut u32::cmp, &mut Probe::new(&mut ()));
+        assert_eq!(arr[42], 42);
+        assert!(arr[..42].iter().all(|&x| x < 42));
+    }
+
+    #[test]
+    fn partial() {
+        for k in [0, 1, 5, 99, 100, 150] {
+            let mut arr = shuffled(100);
+            partial_sort(&mut arr, k);
+
+            let k = k.min(100);
+            assert_eq!(arr[..k], (0..k as u32).collect::<Vec<_>>());
+
+            arr[k..].sort();
+            assert_eq!(arr[k..], (k as u32..100).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn partial_by_key() {
+        let mut arr: Vec<(u32, u32)> = shuffled(30).into_iter().map(|i| (i % 10, i)).collect();
+        partial_sort_by_key(&mut arr, 6, |&(k, _)| k);
+        assert_eq!(
+            arr[..6].iter().map(|&(k, _)| k).collect::<Vec<_>>(),
+            [0, 0, 0, 1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn top() {
+        assert_eq!(top_k(shuffled(1000), 5), [0, 1, 2, 3, 4]);
+        assert_eq!(top_k(shuffled(3), 5), [0, 1, 2]);
+        assert_eq!(top_k(shuffled(10), 0), []);
+        assert_eq!(top_k(Vec::<u32>::new(), 3), []);
+        assert_eq!(top_k(shuffled(4), usize::MAX), [0, 1, 2, 3]);
+        assert_eq!(
+            top_k_by(shuffled(1000), 3, |a, b| b.cmp(a)),
+            [999, 998, 997]
+        );
+        assert_eq!(
+            top_k_by_key(["kiwi", "fig", "banana", "apple"], 2, |s| s.len()),
+            ["fig", "kiwi"]
+        );
+    }
+}
@@ -1,13 +1,44 @@
+use std::cmp::Ordering;
+
+use crate::observe::{Probe, SortObserver};
+
 pub fn selection<T: Ord>(arr: &mut [T]) {
+    selection_by(arr, T::cmp);
+}
+
+pub fn selection_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    selection_observed(arr, compare, &mut ());
+}
+
+pub(crate) fn selection_observed<T, F, O>(arr: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+
     for left in 0..arr.len() {
-        let min = (left..arr.len()).min_by_key(|&i| &arr[i]).unwrap();
-        arr.swap(left, min);
+        let min = (left..arr.len())
+            .min_by(|&i, &j| probe.cmp(arr, i, j, &mut compare))
+            .unwrap();
+        probe.swap(arr, left, min);
     }
 }
 
+pub fn selection_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    selection_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
 #[cfg(test)]
 mod tests {
-    use super::selection;
+    use super::{selection, selection_by, selection_by_key};
 
     #[test]
     fn empty() {
@@ -43,4 +74,25 @@ mod tests {
         selection(&mut arr);
         assert_eq!(arr, [542, 542, 542, 542]);
     }
+
+    #[test]
+    fn reverse() {
+        let mut arr: [char; 4] = ['d', 'a', 'c', 'b'];
+        selection_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, ['d', 'c', 'b', 'a']);
+    }
+
+    #[test]
+    fn float_key() {
+        let mut arr = [2.5, -1.0, 0.5, 3.0];
+        selection_by(&mut arr, f64::total_cmp);
+        assert_eq!(arr, [-1.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        selection_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr.map(|(k, _)| k), [1, 1, 2, 3]);
+    }
 }
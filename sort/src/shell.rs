@@ -1,12 +1,201 @@
+use std::cmp::Ordering;
+
+use crate::observe::{Counter, Probe, SortObserver};
+
+// 希尔排序的间隔序列
+pub trait ShellGaps {
+    // 长度为len的数组要用到的间隔，降序排列且以1结尾
+    fn gaps(&self, len: usize) -> Vec<usize>;
+}
+
+// Knuth：1, 4, 13, 40, ...，h = 3h + 1，最大间隔是首个不小于len / 3的项，总小于len
+pub struct Knuth;
+
+// Sedgewick：1, 8, 23, 77, 281, ...，4ᵏ + 3·2ᵏ⁻¹ + 1
+pub struct Sedgewick;
+
+// Ciura：1, 4, 10, 23, 57, 132, 301, 701 为实验所得，之后按 2.25 倍外推
+pub struct Ciura;
+
+// Tokuda：⌈(9·(9/4)ᵏ - 4) / 5⌉，1, 4, 9, 20, 46, 103, ...
+pub struct Tokuda;
+
+impl ShellGaps for Knuth {
+    fn gaps(&self, len: usize) -> Vec<usize> {
+        let mut gaps = vec![1];
+        let mut h = 1;
+
+        while h < len / 3 {
+            h = 3 * h + 1;
+            gaps.push(h);
+        }
+
+        gaps.reverse();
+        gaps
+    }
+}
+
+impl ShellGaps for Sedgewick {
+    fn gaps(&self, len: usize) -> Vec<usize> {
+        let mut gaps = vec![1];
+
+        for k in 1.. {
+            let h = 4usize.pow(k) + 3 * 2usize.pow(k - 1) + 1;
+            if h >= len {
+                break;
+            }
+            gaps.push(h);
+        }
+
+        gaps.reverse();
+        gaps
+    }
+}
+
+impl ShellGaps for Ciura {
+    fn gaps(&self, len: usize) -> Vec<usize> {
+        const KNOWN: [usize; 8] = [1, 4, 10, 23, 57, 132, 301, 701];
+
+        let mut gaps: Vec<usize> = KNOWN
+            .into_iter()
+            .take_while(|&h| h == 1 || h < len)
+            .collect();
+        let mut h = *gaps.last().unwrap();
+
+        if h == 701 {
+            loop {
+                h = h * 9 / 4;
+                if h >= len {
+                    break;
+                }
+                gaps.push(h);
+            }
+        }
+
+        gaps.reverse();
+        gaps
+    }
+}
+
+impl ShellGaps for Tokuda {
+    fn gaps(&self, len: usize) -> Vec<usize> {
+        let mut gaps = vec![1];
+        let mut power = 9.0 / 4.0;
+
+        loop {
+            let h = ((9.0 * power - 4.0) / 5.0_f64).ceil() as usize;
+            if h >= len {
+                break;
+            }
+            gaps.push(h);
+            power *= 9.0 / 4.0;
+        }
+
+        gaps.reverse();
+        gaps
+    }
+}
+
+// 自定义序列：丢弃不小于len的间隔，并补上末尾的1
+impl ShellGaps for [usize] {
+    fn gaps(&self, len: usize) -> Vec<usize> {
+        let mut gaps: Vec<usize> = self.iter().copied().filter(|&h| h > 1 && h < len).collect();
+
+        gaps.sort_unstable_by(|a, b| b.cmp(a));
+        gaps.dedup();
+        gaps.push(1);
+        gaps
+    }
+}
+
+impl<const N: usize> ShellGaps for [usize; N] {
+    fn gaps(&self, len: usize) -> Vec<usize> {
+        self.as_slice().gaps(len)
+    }
+}
+
+impl ShellGaps for Vec<usize> {
+    fn gaps(&self, len: usize) -> Vec<usize> {
+        self.as_slice().gaps(len)
+    }
+}
+
+// 一次希尔排序的比较与交换次数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ShellStats {
+    pub comparisons: usize,
+    pub swaps: usize,
+}
+
 pub fn shell<T: Ord>(arr: &mut [T]) {
-    // 初始化间隔
-    let mut itrval = 1;
-    while itrval < arr.len() / 3 {
-        itrval = 3 * itrval + 1;
+    shell_by(arr, T::cmp);
+}
+
+pub fn shell_by<T, F>(arr: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    shell_with_by(arr, &Knuth, compare);
+}
+
+pub fn shell_by_key<T, K, F>(arr: &mut [T], mut f: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    shell_by(arr, |a, b| f(a).cmp(&f(b)));
+}
+
+pub fn shell_with<T, G>(arr: &mut [T], gaps: &G)
+where
+    T: Ord,
+    G: ShellGaps + ?Sized,
+{
+    shell_with_by(arr, gaps, T::cmp);
+}
+
+pub fn shell_with_by<T, G, F>(arr: &mut [T], gaps: &G, compare: F)
+where
+    G: ShellGaps + ?Sized,
+    F: FnMut(&T, &T) -> Ordering,
+{
+    shell_observed(arr, gaps, compare, &mut ());
+}
+
+pub(crate) fn shell_observed<T, G, F, O>(arr: &mut [T], gaps: &G, mut compare: F, observer: &mut O)
+where
+    G: ShellGaps + ?Sized,
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
+    let mut probe = Probe::new(observer);
+
+    gapped_insertion(arr, &gaps.gaps(arr.len()), &mut compare, &mut probe);
+}
+
+// 用给定的间隔序列排序，并统计比较与交换次数
+pub fn shell_stats<T, G>(arr: &mut [T], gaps: &G) -> ShellStats
+where
+    T: Ord,
+    G: ShellGaps + ?Sized,
+{
+    let mut counter = Counter::default();
+    shell_observed(arr, gaps, T::cmp, &mut counter);
+
+    ShellStats {
+        comparisons: counter.comparisons,
+        swaps: counter.swaps,
     }
+}
 
+// 依次按各间隔做插入排序
+fn gapped_insertion<T, F, O>(arr: &mut [T], gaps: &[usize], compare: &mut F, probe: &mut Probe<O>)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver + ?Sized,
+{
     // 间隔值为1时变为高完成度的插入排序
-    while itrval >= 1 {
+    for &itrval in gaps {
         // 间隔值的步进至出界，如此能收集到子数组
         // 子数组可能遍历多遍，从后往前比较、交换，
         // 但它是自前部分做插入排序，然后一直囊括后部分，
@@ -15,23 +204,23 @@ pub fn shell<T: Ord>(arr: &mut [T]) {
             // 插入时挪动的距离为间隔值
             for left in (itrval..=right).rev().step_by(itrval) {
                 // 子数组内的值比较
-                if arr[left - itrval] > arr[left] {
-                    arr.swap(left, left - itrval);
+                if probe.cmp(arr, left - itrval, left, compare) == Ordering::Greater {
+                    probe.swap(arr, left, left - itrval);
                 } else {
                     // 子数组内的双双遍历一定会使其有序，不再有额外动作
                     break;
                 }
             }
         }
-
-        // 不断缩小间隔，即减少子数组数量
-        itrval /= 3;
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::shell;
+    use super::{
+        Ciura, Knuth, Sedgewick, ShellGaps, Tokuda, shell, shell_by, shell_by_key, shell_stats,
+        shell_with,
+    };
 
     #[test]
     fn basic() {
@@ -39,4 +228,73 @@ mod tests {
         shell(&mut arr);
         assert_eq!(arr, [2, 3, 4, 5, 6, 7, 8, 9, 10, 13, 14, 16, 17]);
     }
+
+    #[test]
+    fn reverse() {
+        let mut arr: [char; 4] = ['d', 'a', 'c', 'b'];
+        shell_by(&mut arr, |a, b| b.cmp(a));
+        assert_eq!(arr, ['d', 'c', 'b', 'a']);
+    }
+
+    #[test]
+    fn float_key() {
+        let mut arr = [2.5, -1.0, 0.5, 3.0];
+        shell_by(&mut arr, f64::total_cmp);
+        assert_eq!(arr, [-1.0, 0.5, 2.5, 3.0]);
+    }
+
+    #[test]
+    fn by_key() {
+        let mut arr = [(3, 'a'), (1, 'b'), (2, 'c'), (1, 'd')];
+        shell_by_key(&mut arr, |&(k, _)| k);
+        assert_eq!(arr.map(|(k, _)| k), [1, 1, 2, 3]);
+    }
+
+    #[test]
+    fn sequences() {
+        assert_eq!(Knuth.gaps(100), [40, 13, 4, 1]);
+        assert_eq!(Knuth.gaps(15), [13, 4, 1]);
+        assert_eq!(Sedgewick.gaps(300), [281, 77, 23, 8, 1]);
+        assert_eq!(Ciura.gaps(2000), [1577, 701, 301, 132, 57, 23, 10, 4, 1]);
+        assert_eq!(Tokuda.gaps(300), [233, 103, 46, 20, 9, 4, 1]);
+        assert_eq!([10, 1, 4, 500, 4].gaps(100), [10, 4, 1]);
+
+        for len in [0, 1, 2] {
+            assert_eq!(Knuth.gaps(len), [1]);
+            assert_eq!(Sedgewick.gaps(len), [1]);
+            assert_eq!(Ciura.gaps(len), [1]);
+            assert_eq!(Tokuda.gaps(len), [1]);
+        }
+    }
+
+    #[test]
+    fn with_gaps() {
+        let arr: Vec<u32> = (0..1000).map(|i| (i * 7919) % 1009).collect();
+        let mut expected = arr.clone();
+        expected.sort();
+
+        let gaps: [&dyn ShellGaps; 5] = [&Knuth, &Sedgewick, &Ciura, &Tokuda, &[7, 3]];
+        for gaps in gaps {
+            let mut arr = arr.clone();
+            shell_with(&mut arr, gaps);
+            assert_eq!(arr, expected);
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let mut sorted: Vec<u32> = (0..100).collect();
+        let stats = shell_stats(&mut sorted, &Knuth);
+        assert_eq!(stats.swaps, 0);
+        // 每个间隔h都要比较len - h次
+        assert_eq!(
+            stats.comparisons,
+            (100 - 40) + (100 - 13) + (100 - 4) + (100 - 1)
+        );
+
+        let mut reversed: Vec<u32> = (0..100).rev().collect();
+        let stats = shell_stats(&mut reversed, &[1]);
+        assert_eq!(stats.swaps, 100 * 99 / 2);
+        assert!(reversed.is_sorted());
+    }
 }